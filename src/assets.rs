@@ -1,3 +1,9 @@
+use crate::{
+    audio::AudioConfig,
+    model::LevelPreset,
+    render::animation::{Animation, AnimationDef, Animations},
+};
+
 use geng::prelude::*;
 use geng_utils::gif::GifFrame;
 
@@ -5,20 +11,27 @@ use geng_utils::gif::GifFrame;
 pub struct Assets {
     pub sprites: Sprites,
     pub sounds: Sounds,
+    pub fonts: Fonts,
+    #[load(load_with = "load_audio(&manager, &base_path)")]
+    pub audio: AudioAssets,
+    #[load(load_with = "load_levels(&base_path)")]
+    pub levels: Vec<LevelPreset>,
 }
 
 #[derive(geng::asset::Load)]
 pub struct Sprites {
-    pub player: ugli::Texture,
     pub enemy: ugli::Texture,
     pub sword: ugli::Texture,
     pub leaf: ugli::Texture,
-    #[load(load_with = "load_gif(&manager, &base_path.join(\"level_1_bot_idle_normal.gif\"))")]
-    pub level_1_bot_idle_normal: Vec<GifFrame>,
-    #[load(load_with = "load_gif(&manager, &base_path.join(\"level_1_bot_idle_damaged.gif\"))")]
-    pub level_1_bot_idle_damaged: Vec<GifFrame>,
-    #[load(load_with = "load_gif(&manager, &base_path.join(\"level_1_bot_die.gif\"))")]
-    pub level_1_bot_die: Vec<GifFrame>,
+    pub coin: ugli::Texture,
+    #[load(load_with = "load_animations(&manager, &base_path)")]
+    pub animations: Animations,
+}
+
+#[derive(geng::asset::Load)]
+pub struct Fonts {
+    #[load(path = "DejaVuSans.ttf")]
+    pub main: geng::Font,
 }
 
 #[derive(geng::asset::Load)]
@@ -31,6 +44,14 @@ pub struct Sounds {
     pub metal_hit: Vec<geng::Sound>,
     #[load(list = "1..=2", path = "robot-move/robot-move-*.wav")]
     pub robot_move: Vec<geng::Sound>,
+    #[load(list = "1..=2", path = "coin/coin-*.wav")]
+    pub coin_pickup: Vec<geng::Sound>,
+}
+
+pub struct AudioAssets {
+    pub config: AudioConfig,
+    pub day_music: Option<geng::Sound>,
+    pub night_music: Option<geng::Sound>,
 }
 
 impl Assets {
@@ -62,3 +83,96 @@ fn load_gif(
     }
     .boxed_local()
 }
+
+fn load_animations(
+    manager: &geng::asset::Manager,
+    path: &std::path::Path,
+) -> geng::asset::Future<Animations> {
+    let manager = manager.clone();
+    let path = path.to_owned();
+    async move {
+        let defs: HashMap<String, HashMap<String, AnimationDef>> =
+            file::load_detect(path.join("animations.toml"))
+                .await
+                .context("failed to load animation definitions")?;
+        let mut animations = HashMap::new();
+        for (entity, entity_defs) in defs {
+            for (name, def) in entity_defs {
+                let file_path = path.join(&def.file);
+                let frames = if def.file.extension().is_some_and(|ext| ext == "gif") {
+                    load_gif(&manager, &file_path).await?
+                } else {
+                    let texture: ugli::Texture = manager.load(&file_path).await?;
+                    vec![GifFrame {
+                        texture,
+                        duration: 1.0,
+                    }]
+                };
+                animations.insert(
+                    format!("{entity}.{name}"),
+                    Animation {
+                        frames,
+                        playback: def.playback,
+                    },
+                );
+            }
+        }
+        Ok(Animations { animations })
+    }
+    .boxed_local()
+}
+
+fn load_audio(
+    manager: &geng::asset::Manager,
+    path: &std::path::Path,
+) -> geng::asset::Future<AudioAssets> {
+    let manager = manager.clone();
+    let path = path.to_owned();
+    async move {
+        let config: AudioConfig = file::load_detect(path.join("audio.toml"))
+            .await
+            .context("failed to load audio config")?;
+        let day_music = match &config.music.day {
+            Some(track) => Some(load_music(&manager, &path.join(track)).await?),
+            None => None,
+        };
+        let night_music = match &config.music.night {
+            Some(track) => Some(load_music(&manager, &path.join(track)).await?),
+            None => None,
+        };
+        Ok(AudioAssets {
+            config,
+            day_music,
+            night_music,
+        })
+    }
+    .boxed_local()
+}
+
+fn load_levels(path: &std::path::Path) -> geng::asset::Future<Vec<LevelPreset>> {
+    #[derive(Deserialize)]
+    struct LevelsConfig {
+        levels: Vec<LevelPreset>,
+    }
+
+    let path = path.join("levels.toml");
+    async move {
+        let config: LevelsConfig = file::load_detect(&path)
+            .await
+            .context("failed to load level presets")?;
+        Ok(config.levels)
+    }
+    .boxed_local()
+}
+
+async fn load_music(
+    manager: &geng::asset::Manager,
+    path: &std::path::Path,
+) -> anyhow::Result<geng::Sound> {
+    let mut options = <geng::Sound as geng::asset::Load>::Options::default();
+    options.looped = true;
+    manager
+        .load_with(path, &options)
+        .await
+        .with_context(|| format!("failed to load music track {path:?}"))
+}
@@ -0,0 +1,193 @@
+use crate::{assets::Assets, model::*};
+
+use geng::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioSettings {
+    pub master_volume: f32,
+    pub sfx_volume: f32,
+    pub music_volume: f32,
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            sfx_volume: 1.0,
+            music_volume: 0.5,
+            muted: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AudioConfig {
+    pub sounds: SoundsConfig,
+    #[serde(default)]
+    pub music: MusicConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SoundsConfig {
+    pub two_steps: SoundConfig,
+    pub metal_hit: SoundConfig,
+    pub pour_water: SoundConfig,
+    pub robot_move: SoundConfig,
+    pub coin_pickup: SoundConfig,
+}
+
+impl SoundsConfig {
+    pub fn get(&self, sound_kind: &SoundKind) -> &SoundConfig {
+        match sound_kind {
+            SoundKind::TwoSteps => &self.two_steps,
+            SoundKind::MetalHit => &self.metal_hit,
+            SoundKind::PourWater => &self.pour_water,
+            SoundKind::RobotMove => &self.robot_move,
+            SoundKind::CoinPickup => &self.coin_pickup,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SoundConfig {
+    #[serde(default = "default_one")]
+    pub volume: f32,
+    #[serde(default = "default_one")]
+    pub speed: f32,
+    /// Maximum relative deviation of the playback speed, e.g. `0.1` for ±10%.
+    #[serde(default)]
+    pub pitch_jitter: f32,
+}
+
+fn default_one() -> f32 {
+    1.0
+}
+
+/// Paths to music tracks, relative to the `assets` directory.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MusicConfig {
+    pub day: Option<std::path::PathBuf>,
+    pub night: Option<std::path::PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MusicTrack {
+    Day,
+    Night,
+}
+
+impl MusicTrack {
+    pub fn for_state(state: &State) -> Self {
+        match state {
+            State::Night(_) => MusicTrack::Night,
+            State::Day | State::ExpandMap | State::Shop | State::GameOver => MusicTrack::Day,
+        }
+    }
+}
+
+pub struct Audio {
+    assets: Rc<Assets>,
+    settings: AudioSettings,
+    music: Option<(MusicTrack, geng::SoundEffect)>,
+}
+
+impl Audio {
+    pub fn new(assets: &Rc<Assets>, settings: AudioSettings) -> Self {
+        Self {
+            assets: assets.clone(),
+            settings,
+            music: None,
+        }
+    }
+
+    pub fn set_assets(&mut self, assets: &Rc<Assets>) {
+        self.assets = assets.clone();
+        if let Some((_, mut effect)) = self.music.take() {
+            effect.stop();
+        }
+    }
+
+    pub fn settings(&self) -> &AudioSettings {
+        &self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: AudioSettings) {
+        self.settings = settings;
+        let volume = self.music_volume();
+        if let Some((_, effect)) = &mut self.music {
+            effect.set_volume(volume);
+        }
+    }
+
+    pub fn toggle_mute(&mut self) {
+        let mut settings = self.settings.clone();
+        settings.muted = !settings.muted;
+        self.set_settings(settings);
+    }
+
+    fn sfx_volume(&self) -> f32 {
+        if self.settings.muted {
+            return 0.0;
+        }
+        self.settings.master_volume * self.settings.sfx_volume
+    }
+
+    fn music_volume(&self) -> f32 {
+        if self.settings.muted {
+            return 0.0;
+        }
+        self.settings.master_volume * self.settings.music_volume
+    }
+
+    pub fn play_sound(&mut self, sound_kind: &SoundKind) {
+        let sound_variants = match sound_kind {
+            SoundKind::TwoSteps => &self.assets.sounds.two_steps,
+            SoundKind::MetalHit => &self.assets.sounds.metal_hit,
+            SoundKind::PourWater => &self.assets.sounds.pour_water,
+            SoundKind::RobotMove => &self.assets.sounds.robot_move,
+            SoundKind::CoinPickup => &self.assets.sounds.coin_pickup,
+        };
+        let config = self.assets.audio.config.sounds.get(sound_kind);
+        let mut rng = thread_rng();
+        if let Some(sound) = sound_variants.choose(&mut rng) {
+            let jitter = if config.pitch_jitter > 0.0 {
+                rng.gen_range(-config.pitch_jitter..=config.pitch_jitter)
+            } else {
+                0.0
+            };
+            let mut effect = sound.play();
+            effect.set_volume(self.sfx_volume() * config.volume);
+            effect.set_speed(config.speed * (1.0 + jitter));
+        }
+    }
+
+    pub fn update_music(&mut self, state: &State) {
+        let track = MusicTrack::for_state(state);
+        if let Some((current, _)) = &self.music {
+            if *current == track {
+                return;
+            }
+        }
+        if let Some((_, mut effect)) = self.music.take() {
+            effect.stop();
+        }
+        let sound = match track {
+            MusicTrack::Day => &self.assets.audio.day_music,
+            MusicTrack::Night => &self.assets.audio.night_music,
+        };
+        if let Some(sound) = sound {
+            let mut effect = sound.play();
+            effect.set_volume(self.music_volume());
+            self.music = Some((track, effect));
+        }
+    }
+}
+
+impl Drop for Audio {
+    fn drop(&mut self) {
+        if let Some((_, mut effect)) = self.music.take() {
+            effect.stop();
+        }
+    }
+}
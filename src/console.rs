@@ -0,0 +1,299 @@
+use crate::model::*;
+
+use geng::prelude::*;
+
+const MAX_OUTPUT_LINES: usize = 8;
+
+/// Developer console with cheat commands, toggled with the backquote key.
+#[derive(Default)]
+pub struct Console {
+    pub open: bool,
+    pub input: String,
+    /// Previously entered commands and their results, oldest first.
+    pub output: Vec<String>,
+}
+
+pub enum Command {
+    SpawnEnemy(vec2<i64>, EnemyKind),
+    GiveCoins(i64),
+    GiveIngredient(Ingredient),
+    GiveGear(Gear),
+    SetTurns(usize),
+    SetDay(u64),
+    SetState(StateName),
+    Heal,
+    Teleport(vec2<i64>),
+}
+
+pub enum StateName {
+    Day,
+    Expand,
+    Night,
+    GameOver,
+}
+
+impl Console {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.input.clear();
+    }
+
+    /// Edits the input line. Returns the entered command once Enter is pressed.
+    pub fn handle_key(&mut self, key: geng::Key, shift: bool) -> Option<String> {
+        match key {
+            geng::Key::Enter => return Some(std::mem::take(&mut self.input)),
+            geng::Key::Backspace => {
+                self.input.pop();
+            }
+            geng::Key::Escape => self.open = false,
+            _ => {
+                if let Some(c) = key_char(key) {
+                    self.input.push(match (c, shift) {
+                        ('-', true) => '_',
+                        (c, true) => c.to_ascii_uppercase(),
+                        (c, false) => c,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    pub fn execute(&mut self, line: &str, model: &mut Model) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        self.print(format!("> {line}"));
+        match parse(line) {
+            Ok(command) => {
+                command.apply(model);
+                for violation in model.validate() {
+                    self.print(format!("warning: {violation}"));
+                }
+            }
+            Err(err) => self.print(format!("error: {err}")),
+        }
+    }
+
+    fn print(&mut self, line: String) {
+        self.output.push(line);
+        if self.output.len() > MAX_OUTPUT_LINES {
+            self.output.remove(0);
+        }
+    }
+}
+
+fn parse(line: &str) -> anyhow::Result<Command> {
+    let words: Vec<String> = line
+        .split_whitespace()
+        .map(|word| word.to_lowercase())
+        .collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let command = match words.as_slice() {
+        ["spawn", "enemy", x, y] => Command::SpawnEnemy(parse_pos(x, y)?, EnemyKind::Scout),
+        ["spawn", "enemy", x, y, kind] => {
+            Command::SpawnEnemy(parse_pos(x, y)?, parse_enemy_kind(kind)?)
+        }
+        ["give", "coins", amount] => Command::GiveCoins(amount.parse()?),
+        ["give", "ingredient", name] => Command::GiveIngredient(parse_ingredient(name)?),
+        ["give", "gear", name] => Command::GiveGear(parse_gear(name)?),
+        ["set", "turns", turns] => Command::SetTurns(turns.parse()?),
+        ["set", "day", day] => Command::SetDay(day.parse()?),
+        ["state", state] => Command::SetState(match *state {
+            "day" => StateName::Day,
+            "expand" => StateName::Expand,
+            "night" => StateName::Night,
+            "game_over" => StateName::GameOver,
+            _ => anyhow::bail!("unknown state {state:?}, expected day, expand, night or game_over"),
+        }),
+        ["heal"] => Command::Heal,
+        ["teleport", x, y] => Command::Teleport(parse_pos(x, y)?),
+        _ => anyhow::bail!("unknown command"),
+    };
+    Ok(command)
+}
+
+fn parse_pos(x: &str, y: &str) -> anyhow::Result<vec2<i64>> {
+    Ok(vec2(x.parse()?, y.parse()?))
+}
+
+fn parse_enemy_kind(name: &str) -> anyhow::Result<EnemyKind> {
+    Ok(match name {
+        "scout" => EnemyKind::Scout,
+        "brute" => EnemyKind::Brute,
+        "frost" => EnemyKind::Frost,
+        _ => anyhow::bail!("unknown enemy kind {name:?}"),
+    })
+}
+
+fn parse_ingredient(name: &str) -> anyhow::Result<Ingredient> {
+    Ok(match name {
+        "leaf" => Ingredient::Leaf,
+        "ice" => Ingredient::Ice,
+        "branch" => Ingredient::Branch,
+        "banana" => Ingredient::Banana,
+        "cherry" => Ingredient::Cherry,
+        "blueberry" => Ingredient::Blueberry,
+        "strawberry" => Ingredient::Strawberry,
+        _ => anyhow::bail!("unknown ingredient {name:?}"),
+    })
+}
+
+fn parse_gear(name: &str) -> anyhow::Result<Gear> {
+    Ok(match name {
+        "dagger" => Gear::Dagger,
+        "axe" => Gear::Axe,
+        "leather_armour" => Gear::LeatherArmour,
+        "plate_armour" => Gear::PlateArmour,
+        "lucky_charm" => Gear::LuckyCharm,
+        _ => anyhow::bail!("unknown gear {name:?}"),
+    })
+}
+
+impl Command {
+    pub fn apply(self, model: &mut Model) {
+        model.tainted = true;
+        match self {
+            Command::SpawnEnemy(pos, kind) => {
+                let id = model.level_map.add_enemy(pos, kind, model.now());
+                if let Some(EnemyState::Action(cooldown)) =
+                    model.level_map.enemy(id).map(|enemy| &enemy.state)
+                {
+                    model
+                        .timeline
+                        .schedule_at(cooldown.end(), TimedEvent::SettleEnemies);
+                }
+            }
+            Command::GiveCoins(coins) => model.player.backpack.coins += coins,
+            Command::GiveIngredient(ingredient) => {
+                model.player.backpack.ingredients.push(ingredient)
+            }
+            Command::GiveGear(gear) => model.player.backpack.gear.push(gear),
+            Command::SetTurns(turns) => model.player.turns_remaining = turns,
+            Command::SetDay(day) => model.day = day,
+            Command::SetState(state) => match state {
+                StateName::Day => model.phase_day(),
+                StateName::Expand => model.state = State::ExpandMap,
+                StateName::Night => model.phase_night(),
+                StateName::GameOver => model.state = State::GameOver,
+            },
+            Command::Heal => model.player.health = model.player.max_health,
+            Command::Teleport(pos) => {
+                model.player.pos = pos;
+                model.camera.center = pos.map(|x| x as f32);
+            }
+        }
+    }
+}
+
+fn key_char(key: geng::Key) -> Option<char> {
+    use geng::Key;
+    Some(match key {
+        Key::KeyA => 'a',
+        Key::KeyB => 'b',
+        Key::KeyC => 'c',
+        Key::KeyD => 'd',
+        Key::KeyE => 'e',
+        Key::KeyF => 'f',
+        Key::KeyG => 'g',
+        Key::KeyH => 'h',
+        Key::KeyI => 'i',
+        Key::KeyJ => 'j',
+        Key::KeyK => 'k',
+        Key::KeyL => 'l',
+        Key::KeyM => 'm',
+        Key::KeyN => 'n',
+        Key::KeyO => 'o',
+        Key::KeyP => 'p',
+        Key::KeyQ => 'q',
+        Key::KeyR => 'r',
+        Key::KeyS => 's',
+        Key::KeyT => 't',
+        Key::KeyU => 'u',
+        Key::KeyV => 'v',
+        Key::KeyW => 'w',
+        Key::KeyX => 'x',
+        Key::KeyY => 'y',
+        Key::KeyZ => 'z',
+        Key::Digit0 => '0',
+        Key::Digit1 => '1',
+        Key::Digit2 => '2',
+        Key::Digit3 => '3',
+        Key::Digit4 => '4',
+        Key::Digit5 => '5',
+        Key::Digit6 => '6',
+        Key::Digit7 => '7',
+        Key::Digit8 => '8',
+        Key::Digit9 => '9',
+        Key::Space => ' ',
+        Key::Minus => '-',
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(line: &str) -> String {
+        match parse(line) {
+            Ok(_) => panic!("{line:?} should not parse"),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert!(matches!(
+            parse("spawn enemy 1 -2"),
+            Ok(Command::SpawnEnemy(pos, EnemyKind::Scout)) if pos == vec2(1, -2)
+        ));
+        assert!(matches!(
+            parse("Spawn Enemy 0 0 FROST"),
+            Ok(Command::SpawnEnemy(_, EnemyKind::Frost))
+        ));
+        assert!(matches!(parse("give coins 50"), Ok(Command::GiveCoins(50))));
+        assert!(matches!(
+            parse("  state   game_over "),
+            Ok(Command::SetState(StateName::GameOver))
+        ));
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        assert_eq!(parse_error("fly"), "unknown command");
+        assert_eq!(parse_error("heal now"), "unknown command");
+        assert_eq!(
+            parse_error("give ingredient rock"),
+            "unknown ingredient \"rock\""
+        );
+        assert_eq!(
+            parse_error("spawn enemy 0 0 dragon"),
+            "unknown enemy kind \"dragon\""
+        );
+        assert!(parse_error("state dusk").starts_with("unknown state \"dusk\""));
+        // Numbers fail with the error of the number parser.
+        parse_error("give coins lots");
+        parse_error("set turns -1");
+        parse_error("teleport 1");
+    }
+
+    #[test]
+    fn errors_are_printed_to_the_output() {
+        let mut console = Console::default();
+        let mut model = Model::new(0);
+        console.execute("fly", &mut model);
+        assert_eq!(console.output, ["> fly", "error: unknown command"]);
+    }
+
+    #[test]
+    fn commands_taint_the_run() {
+        let mut console = Console::default();
+        let mut model = Model::new(0);
+        console.execute("fly", &mut model);
+        assert!(!model.tainted);
+        console.execute("heal", &mut model);
+        assert!(model.tainted);
+    }
+}
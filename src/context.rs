@@ -0,0 +1,29 @@
+use crate::{assets::Assets, hot_reload::HotReload, locale::Locale, model::*, settings::Settings};
+
+use geng::prelude::*;
+
+/// A game left through the pause menu, resumed with "Continue" on the title screen.
+pub struct SuspendedGame {
+    pub model: Model,
+    pub level: Option<LevelPreset>,
+}
+
+/// State shared by all screens.
+pub struct Context {
+    pub geng: Geng,
+    /// Replaced whenever hot reloading picks up changed assets.
+    pub assets: RefCell<Rc<Assets>>,
+    pub locale: Rc<Locale>,
+    pub settings: RefCell<Settings>,
+    /// Seed from the command line, used by runs that are not started from a level preset.
+    pub seed: Option<u64>,
+    pub console: bool,
+    pub hot_reload: RefCell<Option<HotReload>>,
+    pub suspended: RefCell<Option<SuspendedGame>>,
+}
+
+impl Context {
+    pub fn assets(&self) -> Rc<Assets> {
+        self.assets.borrow().clone()
+    }
+}
@@ -1,97 +1,431 @@
-use crate::{assets::Assets, model::*, render::GameRender};
+use crate::{
+    assets::Assets,
+    audio::Audio,
+    console::Console,
+    context::{Context, SuspendedGame},
+    high_scores::HighScores,
+    menu::{PauseChoice, PauseMenu},
+    model::*,
+    render::{GameRender, UiButton},
+    settings::Control,
+    touch::{Gesture, SwipeDirection, TouchInput},
+};
 
 use geng::prelude::*;
 
+/// Zoom limits for pinch gestures, in world units visible vertically.
+const MIN_FOV: f32 = 5.0;
+const MAX_FOV: f32 = 25.0;
+
 #[allow(dead_code)]
 pub struct Game {
     geng: Geng,
+    context: Rc<Context>,
     assets: Rc<Assets>,
     render: GameRender,
-    model: Model,
+    audio: Audio,
+    console: Option<Console>,
+    /// The run being played, taken out when it is suspended to go back to the main menu.
+    model: Option<Model>,
+    /// Preset the run was started from, reused when restarting.
+    level: Option<LevelPreset>,
+    high_scores: HighScores,
+    /// Place of the last finished run in the high-score table, once it has been recorded.
+    recorded_rank: Option<Option<usize>>,
+    show_high_scores: bool,
+    show_debug: bool,
+    touch: TouchInput,
+    /// Whether on-screen buttons are shown, turned on by the first touch.
+    touch_ui: bool,
     framebuffer_size: vec2<usize>,
     cursor_pos: vec2<f64>,
+    pause_choice: Rc<Cell<Option<PauseChoice>>>,
+    transition: Option<geng::state::Transition>,
 }
 
 impl Game {
-    pub fn new(geng: &Geng, assets: &Rc<Assets>) -> Self {
+    pub fn new(context: &Rc<Context>, level: Option<LevelPreset>) -> Self {
+        let model = new_model(context.seed, level.as_ref());
+        Self::resume(context, SuspendedGame { model, level })
+    }
+
+    pub fn resume(context: &Rc<Context>, suspended: SuspendedGame) -> Self {
+        let assets = context.assets();
         Self {
-            geng: geng.clone(),
-            assets: assets.clone(),
-            render: GameRender::new(geng, assets),
-            model: Model::new(),
+            geng: context.geng.clone(),
+            context: context.clone(),
+            render: GameRender::new(
+                &context.geng,
+                &assets,
+                &context.locale,
+                &context.settings.borrow(),
+            ),
+            audio: Audio::new(&assets, context.settings.borrow().audio.clone()),
+            assets,
+            console: context.console.then(Console::default),
+            model: Some(suspended.model),
+            level: suspended.level,
+            high_scores: HighScores::load(),
+            recorded_rank: None,
+            show_high_scores: false,
+            show_debug: false,
+            touch: TouchInput::default(),
+            touch_ui: false,
             framebuffer_size: vec2(1, 1), // dummy
             cursor_pos: vec2(0.0, 0.0),   // dummy
+            pause_choice: Rc::new(Cell::new(None)),
+            transition: None,
+        }
+    }
+
+    fn set_assets(&mut self, assets: Rc<Assets>) {
+        self.render.set_assets(&assets);
+        self.audio.set_assets(&assets);
+        *self.context.assets.borrow_mut() = assets.clone();
+        self.assets = assets;
+    }
+
+    /// Opens the pause menu on top of a snapshot of the current frame.
+    fn pause(&mut self) {
+        let mut background =
+            ugli::Texture::new_uninitialized(self.geng.ugli(), self.framebuffer_size);
+        {
+            let mut framebuffer = ugli::Framebuffer::new_color(
+                self.geng.ugli(),
+                ugli::ColorAttachment::Texture(&mut background),
+            );
+            geng::State::draw(self, &mut framebuffer);
+        }
+        let menu = PauseMenu::new(&self.context, background, self.pause_choice.clone());
+        self.transition = Some(geng::state::Transition::Push(Box::new(menu)));
+    }
+
+    fn handle_pause_choice(&mut self) {
+        match self.pause_choice.take() {
+            None | Some(PauseChoice::Resume) => {}
+            Some(PauseChoice::Restart) => self.restart(),
+            Some(PauseChoice::MainMenu) => {
+                if let Some(model) = self.model.take() {
+                    if !matches!(model.state, State::GameOver) {
+                        *self.context.suspended.borrow_mut() = Some(SuspendedGame {
+                            model,
+                            level: self.level.clone(),
+                        });
+                    }
+                }
+                self.transition = Some(geng::state::Transition::Pop);
+            }
+        }
+    }
+
+    fn handle_effects(&mut self) {
+        let Some(model) = &mut self.model else {
+            return;
+        };
+        for effect in std::mem::take(&mut model.effects) {
+            match effect {
+                Effect::PlaySound(sound_kind, source) => {
+                    self.audio.play_sound(&sound_kind);
+                    self.render.caption(model, &sound_kind, source);
+                }
+            }
+        }
+    }
+
+    fn key_press(&mut self, key: geng::Key) {
+        if key == geng::Key::Escape {
+            if self.show_high_scores {
+                self.show_high_scores = false;
+                return;
+            }
+            if self
+                .model
+                .as_ref()
+                .is_some_and(|model| model.targeting.is_some())
+            {
+                self.player_input(Action::Cancel);
+                return;
+            }
+        }
+
+        let control = self.context.settings.borrow().keys.control(key);
+        if let Some(control) = control {
+            match control {
+                Control::MoveUp => self.player_input(Action::MoveUp),
+                Control::MoveDown => self.player_input(Action::MoveDown),
+                Control::MoveLeft => self.player_input(Action::MoveLeft),
+                Control::MoveRight => self.player_input(Action::MoveRight),
+                Control::Pause => self.pause(),
+                Control::Mute => self.toggle_mute(),
+                Control::HighScores => self.show_high_scores = !self.show_high_scores,
+                Control::Restart => {
+                    if self
+                        .model
+                        .as_ref()
+                        .is_some_and(|model| matches!(model.state, State::GameOver))
+                    {
+                        self.restart();
+                    }
+                }
+            }
+            return;
+        }
+
+        match key {
+            geng::Key::Digit1 => self.slot_input(0),
+            geng::Key::Digit2 => self.slot_input(1),
+            geng::Key::Digit3 => self.slot_input(2),
+            geng::Key::Digit4 => self.slot_input(3),
+            geng::Key::Digit5 => self.slot_input(4),
+            geng::Key::Digit6 => self.slot_input(5),
+            geng::Key::Digit7 => self.slot_input(6),
+            geng::Key::Digit8 => self.slot_input(7),
+            geng::Key::Digit9 => self.slot_input(8),
+            geng::Key::KeyZ => self.player_input(Action::Unequip(GearSlot::Weapon)),
+            geng::Key::KeyX => self.player_input(Action::Unequip(GearSlot::Armour)),
+            geng::Key::KeyC => self.player_input(Action::Unequip(GearSlot::Trinket)),
+            geng::Key::F3 => self.show_debug = !self.show_debug,
+            _ => {}
+        }
+    }
+
+    fn player_input(&mut self, action: Action) {
+        if let Some(model) = &mut self.model {
+            model.player_input(action);
         }
     }
+
+    fn toggle_mute(&mut self) {
+        self.audio.toggle_mute();
+        let mut settings = self.context.settings.borrow_mut();
+        settings.audio = self.audio.settings().clone();
+        settings.save();
+    }
+
+    fn shift_pressed(&self) -> bool {
+        let window = self.geng.window();
+        window.is_key_pressed(geng::Key::ShiftLeft) || window.is_key_pressed(geng::Key::ShiftRight)
+    }
+
+    /// Number keys use backpack items, or equip carried gear while Shift is held.
+    fn slot_input(&mut self, slot: usize) {
+        if self.shift_pressed() {
+            self.player_input(Action::Equip(slot));
+        } else {
+            self.player_input(Action::UseItem(slot));
+        }
+    }
+
+    fn restart(&mut self) {
+        self.model = Some(new_model(self.context.seed, self.level.as_ref()));
+        self.recorded_rank = None;
+        self.show_high_scores = false;
+    }
+
+    fn record_score(&mut self) {
+        let Some(model) = &self.model else {
+            return;
+        };
+        if let (State::GameOver, None) = (&model.state, self.recorded_rank) {
+            self.recorded_rank = Some(self.high_scores.record(model));
+        }
+    }
+
+    fn press_button(&mut self, button: UiButton) {
+        match button {
+            UiButton::UseItem => self.player_input(Action::UseItem(0)),
+            UiButton::Cancel => self.player_input(Action::Cancel),
+            UiButton::Restart => self.restart(),
+            UiButton::HighScores => self.show_high_scores = !self.show_high_scores,
+            UiButton::Mute => self.toggle_mute(),
+            UiButton::Pause => self.pause(),
+        }
+    }
+
+    fn handle_gesture(&mut self, gesture: Gesture) {
+        match gesture {
+            Gesture::Tap(position) => self.click(position),
+            Gesture::Swipe(direction) => self.player_input(match direction {
+                SwipeDirection::Up => Action::MoveUp,
+                SwipeDirection::Down => Action::MoveDown,
+                SwipeDirection::Left => Action::MoveLeft,
+                SwipeDirection::Right => Action::MoveRight,
+            }),
+            // The tooltip follows the hovered cell, so point it at the pressed cell.
+            Gesture::LongPress(position) => self.cursor_pos = position,
+            Gesture::Pinch(scale) => {
+                if let Some(model) = &mut self.model {
+                    let fov = model.camera.fov * scale as f32;
+                    model.camera.fov = fov.clamp(MIN_FOV, MAX_FOV);
+                }
+            }
+        }
+    }
+
+    fn click(&mut self, screen_pos: vec2<f64>) {
+        let Some(model) = &self.model else {
+            return;
+        };
+        if self.touch_ui {
+            let screen_pos = screen_pos.map(|x| x as f32);
+            if let Some((button, _)) = self
+                .render
+                .touch_buttons(model, self.framebuffer_size)
+                .into_iter()
+                .find(|(_, aabb)| aabb.contains(screen_pos))
+            {
+                self.press_button(button);
+                return;
+            }
+        }
+        if let Some(action) =
+            self.render
+                .hud_action_at(model, self.framebuffer_size, screen_pos.map(|x| x as f32))
+        {
+            self.player_input(action);
+            return;
+        }
+        let pos = screen_to_cell(model, self.framebuffer_size, screen_pos);
+        self.player_input(Action::MoveTo(pos));
+    }
+}
+
+fn screen_to_cell(
+    model: &Model,
+    framebuffer_size: vec2<usize>,
+    screen_pos: vec2<f64>,
+) -> vec2<i64> {
+    model
+        .camera
+        .screen_to_world(
+            framebuffer_size.map(|x| x as f32),
+            screen_pos.map(|x| x as f32),
+        )
+        .map(|x| (x + 0.5).floor() as i64)
 }
 
 impl geng::State for Game {
     fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
         ugli::clear(framebuffer, Some(Rgba::BLACK), None, None);
-        self.render.draw(&self.model, framebuffer);
+        let Some(model) = &self.model else {
+            return;
+        };
+        let hovered_cell = screen_to_cell(model, self.framebuffer_size, self.cursor_pos);
+        self.render.draw(model, Some(hovered_cell), framebuffer);
+        if self.show_debug {
+            self.render.draw_debug(model, framebuffer);
+        }
+        if self.touch_ui {
+            self.render.draw_touch_buttons(model, framebuffer);
+        }
+        if self.show_high_scores {
+            self.render.draw_high_scores(
+                &self.high_scores,
+                self.recorded_rank.flatten(),
+                framebuffer,
+            );
+        }
+        if let Some(console) = &self.console {
+            if console.open {
+                self.render.draw_console(console, framebuffer);
+            }
+        }
         self.framebuffer_size = framebuffer.size();
     }
 
     fn handle_event(&mut self, event: geng::Event) {
+        if let geng::Event::KeyPress { key } = &event {
+            let shift = self.shift_pressed();
+            if let Some(console) = &mut self.console {
+                if let geng::Key::Backquote = key {
+                    console.toggle();
+                    return;
+                }
+                if console.open {
+                    if let Some(line) = console.handle_key(*key, shift) {
+                        if let Some(model) = &mut self.model {
+                            console.execute(&line, model);
+                        }
+                        self.handle_effects();
+                    }
+                    return;
+                }
+            }
+        }
+
         match event {
-            geng::Event::KeyPress { key } => match key {
-                geng::Key::ArrowDown => self.model.player_input(Action::MoveDown),
-                geng::Key::ArrowUp => self.model.player_input(Action::MoveUp),
-                geng::Key::ArrowLeft => self.model.player_input(Action::MoveLeft),
-                geng::Key::ArrowRight => self.model.player_input(Action::MoveRight),
-                _ => {}
-            },
+            geng::Event::KeyPress { key } => self.key_press(key),
             geng::Event::MouseRelease { button } => match button {
-                geng::MouseButton::Left => {
-                    let pos = self
-                        .model
-                        .camera
-                        .screen_to_world(
-                            self.framebuffer_size.map(|x| x as f32),
-                            self.cursor_pos.map(|x| x as f32),
-                        )
-                        .map(|x| (x + 0.5).floor() as i64);
-                    self.model.player_input(Action::MoveTo(pos));
-                }
+                geng::MouseButton::Left => self.click(self.cursor_pos),
                 geng::MouseButton::Middle => {}
-                geng::MouseButton::Right => {}
+                geng::MouseButton::Right => self.player_input(Action::Cancel),
             },
+            geng::Event::TouchStart(touch) => {
+                self.touch_ui = true;
+                self.touch.touch_start(touch);
+            }
+            geng::Event::TouchMove(touch) => {
+                if let Some(gesture) = self.touch.touch_move(touch) {
+                    self.handle_gesture(gesture);
+                }
+            }
             geng::Event::TouchEnd(touch) => {
-                let pos = self
-                    .model
-                    .camera
-                    .screen_to_world(
-                        self.framebuffer_size.map(|x| x as f32),
-                        touch.position.map(|x| x as f32),
-                    )
-                    .map(|x| (x + 0.5).floor() as i64);
-                self.model.player_input(Action::MoveTo(pos));
+                if let Some(gesture) = self.touch.touch_end(touch) {
+                    self.handle_gesture(gesture);
+                }
             }
             geng::Event::CursorMove { position } => {
                 self.cursor_pos = position;
             }
             _ => {}
         }
-        for effect in std::mem::take(&mut self.model.effects) {
-            match effect {
-                Effect::PlaySound(sound_kind) => {
-                    let sound_variants = match sound_kind {
-                        SoundKind::TwoSteps => &self.assets.sounds.two_steps,
-                        SoundKind::MetalHit => &self.assets.sounds.metal_hit,
-                        SoundKind::PourWater => &self.assets.sounds.pour_water,
-                        SoundKind::RobotMove => &self.assets.sounds.robot_move,
-                    };
-                    if let Some(sound) = sound_variants.choose(&mut thread_rng()) {
-                        let mut effect = sound.play();
-                        effect.set_speed(1.5);
-                    }
-                }
-            }
-        }
+        self.handle_effects();
     }
 
     fn update(&mut self, delta_time: f64) {
-        let delta_time = Time::new(delta_time as _);
-        self.model.update(delta_time);
+        self.handle_pause_choice();
+
+        let reloaded = self
+            .context
+            .hot_reload
+            .borrow_mut()
+            .as_mut()
+            .and_then(|hot_reload| hot_reload.update(self.geng.asset_manager()));
+        if let Some(assets) = reloaded {
+            self.set_assets(Rc::new(assets));
+        }
+
+        if let Some(gesture) = self.touch.update(delta_time) {
+            self.handle_gesture(gesture);
+        }
+
+        self.render.update(delta_time as f32);
+
+        let speed = self.context.settings.borrow().animation_speed;
+        let delta_time = Time::new((delta_time * speed as f64) as _);
+        let Some(model) = &mut self.model else {
+            return;
+        };
+        model.update(delta_time);
+        self.audio.update_music(&model.state);
+        self.handle_effects();
+        self.record_score();
+    }
+
+    fn transition(&mut self) -> Option<geng::state::Transition> {
+        self.transition.take()
+    }
+}
+
+fn new_model(seed: Option<u64>, level: Option<&LevelPreset>) -> Model {
+    let seed = level
+        .and_then(|level| level.seed)
+        .or(seed)
+        .unwrap_or_else(|| thread_rng().gen());
+    log::info!("starting a run with seed {seed}");
+    let mut model = Model::new(seed);
+    if let Some(level) = level {
+        level.apply(&mut model);
     }
+    model
 }
@@ -0,0 +1,84 @@
+use crate::model::Model;
+
+use geng::prelude::*;
+
+const PREFERENCES_KEY: &str = "high_scores";
+const MAX_ENTRIES: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub score: u64,
+    pub days_survived: u64,
+    pub enemies_defeated: u64,
+    pub potions_brewed: u64,
+    pub coins: i64,
+    pub seed: u64,
+    /// Date the run finished at, as `YYYY-MM-DD` in UTC.
+    pub date: String,
+}
+
+/// Best runs on this machine, stored in the local preferences.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    pub entries: Vec<HighScoreEntry>,
+}
+
+impl HighScores {
+    pub fn load() -> Self {
+        preferences::load(PREFERENCES_KEY).unwrap_or_default()
+    }
+
+    fn save(&self) {
+        preferences::save(PREFERENCES_KEY, self);
+    }
+
+    /// Records a finished run and returns its place in the table, if it made it in.
+    /// Runs changed through the console are never recorded.
+    pub fn record(&mut self, model: &Model) -> Option<usize> {
+        if model.tainted {
+            return None;
+        }
+        let score = model.score();
+        let entry = HighScoreEntry {
+            score: score.total(),
+            days_survived: score.days_survived,
+            enemies_defeated: score.enemies_defeated,
+            potions_brewed: score.potions_brewed,
+            coins: score.coins,
+            seed: model.seed,
+            date: today(),
+        };
+        let rank = self
+            .entries
+            .partition_point(|other| other.score >= entry.score);
+        if rank >= MAX_ENTRIES {
+            return None;
+        }
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_ENTRIES);
+        self.save();
+        Some(rank)
+    }
+}
+
+fn today() -> String {
+    let secs = web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    civil_date((secs / 86400) as i64)
+}
+
+/// Converts days since the Unix epoch into a `YYYY-MM-DD` date of the Gregorian calendar.
+fn civil_date(days: i64) -> String {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
@@ -0,0 +1,114 @@
+use crate::assets::Assets;
+
+use geng::prelude::*;
+
+type AssetsFuture = futures::future::LocalBoxFuture<'static, anyhow::Result<Assets>>;
+
+/// Watches the assets directory and reloads [Assets] whenever something changes.
+///
+/// Any data file loaded as part of [Assets] is picked up as well.
+/// String tables are compiled into the binary (see [crate::locale]), so changes under
+/// `locale/` are ignored and need a rebuild.
+pub struct HotReload {
+    #[cfg(not(target_arch = "wasm32"))]
+    watcher: AssetWatcher,
+    pending: Option<AssetsFuture>,
+}
+
+impl HotReload {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new(path: &std::path::Path) -> anyhow::Result<Self> {
+        Ok(Self {
+            watcher: AssetWatcher::new(path)?,
+            pending: None,
+        })
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn new(_path: &std::path::Path) -> anyhow::Result<Self> {
+        anyhow::bail!("hot reloading is not supported on the web")
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn changed(&mut self) -> bool {
+        self.watcher.changed()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn changed(&mut self) -> bool {
+        false
+    }
+
+    /// Returns freshly loaded assets once a reload triggered by a file change has finished.
+    pub fn update(&mut self, manager: &geng::asset::Manager) -> Option<Assets> {
+        if self.changed() {
+            log::info!("assets changed, reloading");
+            let manager = manager.clone();
+            self.pending = Some(async move { Assets::load(&manager).await }.boxed_local());
+        }
+
+        let pending = self.pending.as_mut()?;
+        let mut context = std::task::Context::from_waker(futures::task::noop_waker_ref());
+        let std::task::Poll::Ready(result) = pending.poll_unpin(&mut context) else {
+            return None;
+        };
+        self.pending = None;
+        match result {
+            Ok(assets) => {
+                log::info!("assets reloaded");
+                Some(assets)
+            }
+            Err(err) => {
+                log::error!("failed to reload assets: {err:?}");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct AssetWatcher {
+    _watcher: notify::RecommendedWatcher,
+    /// Directory of the string tables, which are not part of [Assets].
+    locale: std::path::PathBuf,
+    events: std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl AssetWatcher {
+    fn new(path: &std::path::Path) -> anyhow::Result<Self> {
+        use notify::Watcher;
+
+        let (sender, events) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher
+            .watch(path, notify::RecursiveMode::Recursive)
+            .with_context(|| format!("failed to watch {path:?}"))?;
+        Ok(Self {
+            _watcher: watcher,
+            locale: path.join("locale"),
+            events,
+        })
+    }
+
+    fn changed(&self) -> bool {
+        let mut changed = false;
+        for event in self.events.try_iter() {
+            match event {
+                Ok(event) => {
+                    changed |= matches!(
+                        event.kind,
+                        notify::EventKind::Create(_)
+                            | notify::EventKind::Modify(_)
+                            | notify::EventKind::Remove(_)
+                    ) && !event
+                        .paths
+                        .iter()
+                        .all(|path| path.starts_with(&self.locale));
+                }
+                Err(err) => log::warn!("asset watcher error: {err}"),
+            }
+        }
+        changed
+    }
+}
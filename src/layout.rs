@@ -0,0 +1,102 @@
+use geng::prelude::*;
+
+/// Screen height the UI sizes are designed for.
+const REFERENCE_HEIGHT: f32 = 720.0;
+/// Distance kept from the screen edges, in reference pixels.
+const SAFE_MARGIN: f32 = 16.0;
+/// Extra margin on the short edges of a portrait screen, as a fraction of its height,
+/// to stay clear of notches and rounded corners.
+const PORTRAIT_INSET: f32 = 0.03;
+
+/// Point of a rectangle that an element is attached to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Center,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Position of the anchor inside a unit square, `(0, 0)` being the bottom left corner.
+    fn alignment(self) -> vec2<f32> {
+        match self {
+            Anchor::TopLeft => vec2(0.0, 1.0),
+            Anchor::Top => vec2(0.5, 1.0),
+            Anchor::TopRight => vec2(1.0, 1.0),
+            Anchor::Center => vec2(0.5, 0.5),
+            Anchor::BottomLeft => vec2(0.0, 0.0),
+            Anchor::Bottom => vec2(0.5, 0.0),
+            Anchor::BottomRight => vec2(1.0, 0.0),
+        }
+    }
+}
+
+/// Places UI elements on screens of any size and aspect ratio.
+///
+/// Sizes and offsets are given in reference pixels, which are scaled with the
+/// shorter side of the screen, and positions are taken relative to the safe area.
+#[derive(Debug, Clone, Copy)]
+pub struct Layout {
+    screen: Aabb2<f32>,
+    safe_area: Aabb2<f32>,
+    scale: f32,
+}
+
+impl Layout {
+    pub fn new(framebuffer_size: vec2<usize>) -> Self {
+        let size = framebuffer_size.map(|x| x as f32);
+        let scale = (size.x.min(size.y) / REFERENCE_HEIGHT).clamp(0.5, 2.0);
+        let mut margin = vec2(SAFE_MARGIN, SAFE_MARGIN) * scale;
+        if size.y > size.x {
+            margin.y += size.y * PORTRAIT_INSET;
+        }
+        let screen = Aabb2::ZERO.extend_positive(size);
+        Self {
+            screen,
+            safe_area: screen.extend_symmetric(-margin),
+            scale,
+        }
+    }
+
+    pub fn screen(&self) -> Aabb2<f32> {
+        self.screen
+    }
+
+    pub fn is_portrait(&self) -> bool {
+        self.screen.height() > self.screen.width()
+    }
+
+    /// Converts reference pixels to screen pixels.
+    pub fn px(&self, value: f32) -> f32 {
+        value * self.scale
+    }
+
+    /// Point of the safe area at `anchor`, moved by `offset` reference pixels.
+    pub fn point(&self, anchor: Anchor, offset: vec2<f32>) -> vec2<f32> {
+        let alignment = anchor.alignment();
+        self.safe_area.min + self.safe_area.size() * alignment + offset * self.scale
+    }
+
+    /// A box of `size` reference pixels whose `anchor` point sits at the same
+    /// point of the safe area, moved by `offset` reference pixels.
+    pub fn place(&self, anchor: Anchor, offset: vec2<f32>, size: vec2<f32>) -> Aabb2<f32> {
+        let size = size * self.scale;
+        let point = self.point(anchor, offset);
+        let min = point - size * anchor.alignment();
+        Aabb2::point(min).extend_positive(size)
+    }
+
+    /// Width of the safe area in reference pixels, for fitting rows of elements.
+    pub fn available_width(&self) -> f32 {
+        self.safe_area.width() / self.scale
+    }
+
+    /// Height of the safe area in reference pixels.
+    pub fn available_height(&self) -> f32 {
+        self.safe_area.height() / self.scale
+    }
+}
@@ -0,0 +1,68 @@
+use geng::prelude::*;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Language {
+    En,
+    #[default]
+    Ru,
+}
+
+impl Language {
+    /// String tables are built into the binary, so editing them needs a rebuild;
+    /// hot reloading skips them.
+    fn source(&self) -> &'static str {
+        match self {
+            Language::En => include_str!("../assets/locale/en.toml"),
+            Language::Ru => include_str!("../assets/locale/ru.toml"),
+        }
+    }
+}
+
+pub struct Locale {
+    pub language: Language,
+    strings: HashMap<String, String>,
+}
+
+impl Locale {
+    pub fn new(language: Language) -> Self {
+        let table: toml::Table = toml::from_str(language.source()).unwrap_or_else(|err| {
+            log::error!("failed to parse string table for {language:?}: {err}");
+            toml::Table::new()
+        });
+        let mut strings = HashMap::new();
+        flatten_table("", &table, &mut strings);
+        Self { language, strings }
+    }
+
+    /// Looks up a string by its dotted key, falling back to the key itself.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings.get(key).map_or(key, |text| text.as_str())
+    }
+
+    /// Looks up a string and substitutes `{name}` placeholders.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+        let mut text = self.get(key).to_owned();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+        text
+    }
+}
+
+fn flatten_table(prefix: &str, table: &toml::Table, strings: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            toml::Value::String(text) => {
+                strings.insert(key, text.clone());
+            }
+            toml::Value::Table(table) => flatten_table(&key, table, strings),
+            _ => log::warn!("ignoring non-string translation for {key:?}"),
+        }
+    }
+}
@@ -1,12 +1,44 @@
 mod assets;
+mod audio;
+mod console;
+mod context;
 mod game;
+mod high_scores;
+mod hot_reload;
+mod layout;
+mod locale;
+mod menu;
 mod model;
 mod render;
+mod settings;
+mod sim;
+mod touch;
 
 use geng::prelude::*;
 
 #[derive(clap::Parser)]
 struct Opts {
+    /// Overrides the language chosen on the settings screen.
+    #[clap(long, value_enum)]
+    lang: Option<locale::Language>,
+    /// Watch the assets directory and reload changed files while the game is running.
+    #[clap(long)]
+    hot_reload: bool,
+    /// Enable the developer console (always available in debug builds).
+    #[clap(long)]
+    console: bool,
+    /// Seed for level generation; a random one is picked for every run if omitted.
+    #[clap(long)]
+    seed: Option<u64>,
+    /// Play this many headless games with an automated player and print statistics.
+    #[clap(long)]
+    simulate: Option<usize>,
+    /// Automated player used by `--simulate`.
+    #[clap(long, value_enum, default_value_t)]
+    agent: sim::AgentKind,
+    /// Nights a simulated run has to survive to count as a win.
+    #[clap(long, default_value_t = 10)]
+    target_days: u64,
     #[clap(flatten)]
     geng: geng::CliArgs,
 }
@@ -17,14 +49,47 @@ fn main() {
 
     let opts: Opts = clap::Parser::parse();
 
+    if let Some(games) = opts.simulate {
+        let report = sim::run(&sim::SimConfig {
+            games,
+            first_seed: opts.seed.unwrap_or(0),
+            agent: opts.agent,
+            target_days: opts.target_days,
+            max_actions: 10_000,
+        });
+        println!("{report}");
+        return;
+    }
+
+    let settings = settings::Settings::load();
+    let locale = Rc::new(locale::Locale::new(opts.lang.unwrap_or(settings.language)));
+
     let mut geng_options = geng::ContextOptions::default();
-    geng_options.window.title = "Путешествие во времени".to_string();
+    geng_options.window.title = locale.get("title").to_string();
+    geng_options.window.fullscreen = settings.fullscreen;
+    geng_options.window.size = Some(settings.window_size);
     geng_options.with_cli(&opts.geng);
 
     Geng::run_with(&geng_options, |geng| async move {
         let manager = geng.asset_manager();
         let assets = assets::Assets::load(manager).await.unwrap();
-        let state = game::Game::new(&geng, &Rc::new(assets));
-        geng.run_state(state).await;
+        let hot_reload = if opts.hot_reload {
+            hot_reload::HotReload::new(&run_dir().join("assets"))
+                .map_err(|err| log::error!("failed to enable hot reloading: {err:?}"))
+                .ok()
+        } else {
+            None
+        };
+        let context = Rc::new(context::Context {
+            geng: geng.clone(),
+            assets: RefCell::new(Rc::new(assets)),
+            locale,
+            settings: RefCell::new(settings),
+            seed: opts.seed,
+            console: opts.console || cfg!(debug_assertions),
+            hot_reload: RefCell::new(hot_reload),
+            suspended: RefCell::new(None),
+        });
+        geng.run_state(menu::MainMenu::new(&context)).await;
     });
 }
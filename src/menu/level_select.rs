@@ -0,0 +1,76 @@
+use super::*;
+use crate::{context::Context, game::Game};
+
+#[derive(Debug, Clone, Copy)]
+enum LevelSelectItem {
+    Level(usize),
+    Back,
+}
+
+pub struct LevelSelect {
+    context: Rc<Context>,
+    menu: MenuList<LevelSelectItem>,
+    transition: Option<geng::state::Transition>,
+}
+
+impl LevelSelect {
+    pub fn new(context: &Rc<Context>) -> Self {
+        let locale = &context.locale;
+        let mut items: Vec<_> = context
+            .assets()
+            .levels
+            .iter()
+            .enumerate()
+            .map(|(index, level)| {
+                MenuItem::new(
+                    LevelSelectItem::Level(index),
+                    locale.get(&format!("level.{}", level.id)),
+                )
+            })
+            .collect();
+        items.push(MenuItem::new(
+            LevelSelectItem::Back,
+            locale.get("menu.back"),
+        ));
+        Self {
+            context: context.clone(),
+            menu: MenuList::new(locale.get("menu.level_select"), items),
+            transition: None,
+        }
+    }
+}
+
+impl geng::State for LevelSelect {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(Rgba::BLACK), None, None);
+        let assets = self.context.assets();
+        self.menu
+            .draw(&self.context.geng, &assets.fonts.main, framebuffer);
+    }
+
+    fn handle_event(&mut self, event: geng::Event) {
+        if let geng::Event::KeyPress {
+            key: geng::Key::Escape,
+        } = event
+        {
+            self.transition = Some(geng::state::Transition::Pop);
+            return;
+        }
+        match self.menu.handle_event(&event) {
+            Some(LevelSelectItem::Level(index)) => {
+                let Some(level) = self.context.assets().levels.get(index).cloned() else {
+                    return;
+                };
+                *self.context.suspended.borrow_mut() = None;
+                let game = Game::new(&self.context, Some(level));
+                self.transition = Some(geng::state::Transition::Switch(Box::new(game)));
+            }
+            Some(LevelSelectItem::Back) => self.transition = Some(geng::state::Transition::Pop),
+            None => {}
+        }
+    }
+
+    fn transition(&mut self) -> Option<geng::state::Transition> {
+        self.transition.take()
+    }
+}
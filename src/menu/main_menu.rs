@@ -0,0 +1,87 @@
+use super::*;
+use crate::{context::Context, game::Game};
+
+#[derive(Debug, Clone, Copy)]
+pub enum MainMenuItem {
+    NewGame,
+    Continue,
+    LevelSelect,
+    Settings,
+    Quit,
+}
+
+const CONTINUE_INDEX: usize = 1;
+
+/// Title screen, the bottom of the screen stack.
+pub struct MainMenu {
+    context: Rc<Context>,
+    menu: MenuList<MainMenuItem>,
+    transition: Option<geng::state::Transition>,
+}
+
+impl MainMenu {
+    pub fn new(context: &Rc<Context>) -> Self {
+        let locale = &context.locale;
+        let mut items = vec![
+            MenuItem::new(MainMenuItem::NewGame, locale.get("menu.new_game")),
+            MenuItem::new(MainMenuItem::Continue, locale.get("menu.continue")),
+            MenuItem::new(MainMenuItem::LevelSelect, locale.get("menu.level_select")),
+            MenuItem::new(MainMenuItem::Settings, locale.get("menu.settings")),
+        ];
+        // Closing the tab is the way to quit on the web.
+        if cfg!(not(target_arch = "wasm32")) {
+            items.push(MenuItem::new(MainMenuItem::Quit, locale.get("menu.quit")));
+        }
+        Self {
+            context: context.clone(),
+            menu: MenuList::new(locale.get("title"), items),
+            transition: None,
+        }
+    }
+
+    fn select(&mut self, item: MainMenuItem) {
+        let screen: Box<dyn geng::State> = match item {
+            MainMenuItem::NewGame => {
+                *self.context.suspended.borrow_mut() = None;
+                Box::new(Game::new(&self.context, None))
+            }
+            MainMenuItem::Continue => {
+                let Some(suspended) = self.context.suspended.take() else {
+                    return;
+                };
+                Box::new(Game::resume(&self.context, suspended))
+            }
+            MainMenuItem::LevelSelect => Box::new(LevelSelect::new(&self.context)),
+            MainMenuItem::Settings => Box::new(SettingsScreen::new(&self.context)),
+            MainMenuItem::Quit => {
+                self.transition = Some(geng::state::Transition::Pop);
+                return;
+            }
+        };
+        self.transition = Some(geng::state::Transition::Push(screen));
+    }
+}
+
+impl geng::State for MainMenu {
+    fn update(&mut self, _delta_time: f64) {
+        let can_continue = self.context.suspended.borrow().is_some();
+        self.menu.set_enabled(CONTINUE_INDEX, can_continue);
+    }
+
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(Rgba::BLACK), None, None);
+        let assets = self.context.assets();
+        self.menu
+            .draw(&self.context.geng, &assets.fonts.main, framebuffer);
+    }
+
+    fn handle_event(&mut self, event: geng::Event) {
+        if let Some(item) = self.menu.handle_event(&event) {
+            self.select(item);
+        }
+    }
+
+    fn transition(&mut self) -> Option<geng::state::Transition> {
+        self.transition.take()
+    }
+}
@@ -0,0 +1,192 @@
+mod level_select;
+mod main_menu;
+mod pause;
+mod settings;
+
+pub use self::level_select::*;
+pub use self::main_menu::*;
+pub use self::pause::*;
+pub use self::settings::*;
+
+use crate::layout::{Anchor, Layout};
+
+use geng::{prelude::*, TextAlign};
+
+pub struct MenuItem<T> {
+    pub action: T,
+    pub label: String,
+    pub enabled: bool,
+}
+
+impl<T> MenuItem<T> {
+    pub fn new(action: T, label: &str) -> Self {
+        Self {
+            action,
+            label: label.to_string(),
+            enabled: true,
+        }
+    }
+}
+
+/// A vertical list of entries navigated with arrow keys, mouse or touch.
+pub struct MenuList<T> {
+    pub title: String,
+    pub items: Vec<MenuItem<T>>,
+    selected: usize,
+    cursor_pos: vec2<f64>,
+    /// Layout of the last drawn frame, used for picking entries.
+    layout: Layout,
+}
+
+impl<T: Copy> MenuList<T> {
+    pub fn new(title: &str, items: Vec<MenuItem<T>>) -> Self {
+        let mut menu = Self {
+            title: title.to_string(),
+            items,
+            selected: 0,
+            cursor_pos: vec2(0.0, 0.0),
+            layout: Layout::new(vec2(1, 1)),
+        };
+        menu.fix_selection();
+        menu
+    }
+
+    pub fn set_enabled(&mut self, index: usize, enabled: bool) {
+        if let Some(item) = self.items.get_mut(index) {
+            item.enabled = enabled;
+        }
+        self.fix_selection();
+    }
+
+    /// Moves the selection off a disabled entry.
+    fn fix_selection(&mut self) {
+        if !self
+            .items
+            .get(self.selected)
+            .is_some_and(|item| item.enabled)
+        {
+            self.move_selection(1);
+        }
+    }
+
+    fn move_selection(&mut self, step: isize) {
+        let count = self.items.len() as isize;
+        for i in 1..=count {
+            let index = (self.selected as isize + step * i).rem_euclid(count) as usize;
+            if self.items[index].enabled {
+                self.selected = index;
+                return;
+            }
+        }
+    }
+
+    /// Vertical distance between entries, shrunk so that long lists fit on screen.
+    fn item_step(&self) -> f32 {
+        let available = self.layout.available_height() - 160.0;
+        (available / self.items.len().max(1) as f32).clamp(24.0, 60.0)
+    }
+
+    fn item_aabb(&self, index: usize) -> Aabb2<f32> {
+        let step = self.item_step();
+        let size = vec2(420.0_f32.min(self.layout.available_width()), step * 0.8);
+        let total_height = self.items.len() as f32 * step;
+        let top = total_height / 2.0 - 40.0;
+        self.layout.place(
+            Anchor::Center,
+            vec2(0.0, top - (index as f32 + 0.5) * step),
+            size,
+        )
+    }
+
+    fn item_at(&self, screen_pos: vec2<f64>) -> Option<usize> {
+        let screen_pos = screen_pos.map(|x| x as f32);
+        (0..self.items.len())
+            .find(|&index| self.items[index].enabled && self.item_aabb(index).contains(screen_pos))
+    }
+
+    /// Returns the action of an entry once it is activated.
+    pub fn handle_event(&mut self, event: &geng::Event) -> Option<T> {
+        let activated = match event {
+            geng::Event::KeyPress { key } => match key {
+                geng::Key::ArrowUp => {
+                    self.move_selection(-1);
+                    None
+                }
+                geng::Key::ArrowDown => {
+                    self.move_selection(1);
+                    None
+                }
+                geng::Key::Enter | geng::Key::Space => Some(self.selected),
+                _ => None,
+            },
+            geng::Event::CursorMove { position } => {
+                self.cursor_pos = *position;
+                if let Some(index) = self.item_at(*position) {
+                    self.selected = index;
+                }
+                None
+            }
+            geng::Event::MouseRelease {
+                button: geng::MouseButton::Left,
+            } => self.item_at(self.cursor_pos),
+            geng::Event::TouchEnd(touch) => self.item_at(touch.position),
+            _ => None,
+        };
+        let item = self.items.get(activated?)?;
+        item.enabled.then_some(item.action)
+    }
+
+    /// Left and right arrows step the value of the selected entry down or up.
+    pub fn adjustment(&self, event: &geng::Event) -> Option<(T, i32)> {
+        let step = match event {
+            geng::Event::KeyPress {
+                key: geng::Key::ArrowLeft,
+            } => -1,
+            geng::Event::KeyPress {
+                key: geng::Key::ArrowRight,
+            } => 1,
+            _ => return None,
+        };
+        let item = self.items.get(self.selected)?;
+        item.enabled.then_some((item.action, step))
+    }
+
+    pub fn draw(&mut self, geng: &Geng, font: &geng::Font, framebuffer: &mut ugli::Framebuffer) {
+        self.layout = Layout::new(framebuffer.size());
+        let title_pos = vec2(
+            self.layout.point(Anchor::Center, vec2(0.0, 0.0)).x,
+            self.item_aabb(0).max.y + self.layout.px(64.0),
+        );
+        font.draw(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            &self.title,
+            vec2(TextAlign::CENTER, TextAlign::CENTER),
+            mat3::translate(title_pos) * mat3::scale_uniform(self.layout.px(56.0)),
+            Rgba::WHITE,
+        );
+        for (index, item) in self.items.iter().enumerate() {
+            let aabb = self.item_aabb(index);
+            let background = if index == self.selected {
+                Rgba::new(0.4, 0.4, 0.25, 0.9)
+            } else {
+                Rgba::new(0.2, 0.2, 0.2, 0.9)
+            };
+            geng.draw2d()
+                .quad(framebuffer, &geng::PixelPerfectCamera, aabb, background);
+            let color = if item.enabled {
+                Rgba::WHITE
+            } else {
+                Rgba::new(0.5, 0.5, 0.5, 1.0)
+            };
+            font.draw(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                &item.label,
+                vec2(TextAlign::CENTER, TextAlign::CENTER),
+                mat3::translate(aabb.center()) * mat3::scale_uniform(aabb.height() * 0.55),
+                color,
+            );
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use super::*;
+use crate::context::Context;
+
+/// What the game should do once the pause menu closes.
+#[derive(Debug, Clone, Copy)]
+pub enum PauseChoice {
+    Resume,
+    Restart,
+    MainMenu,
+}
+
+/// Overlay shown over a snapshot of the paused game.
+pub struct PauseMenu {
+    context: Rc<Context>,
+    background: ugli::Texture,
+    menu: MenuList<PauseChoice>,
+    choice: Rc<Cell<Option<PauseChoice>>>,
+    transition: Option<geng::state::Transition>,
+}
+
+impl PauseMenu {
+    pub fn new(
+        context: &Rc<Context>,
+        background: ugli::Texture,
+        choice: Rc<Cell<Option<PauseChoice>>>,
+    ) -> Self {
+        let locale = &context.locale;
+        let items = vec![
+            MenuItem::new(PauseChoice::Resume, locale.get("menu.resume")),
+            MenuItem::new(PauseChoice::Restart, locale.get("menu.restart")),
+            MenuItem::new(PauseChoice::MainMenu, locale.get("menu.main_menu")),
+        ];
+        Self {
+            context: context.clone(),
+            background,
+            menu: MenuList::new(locale.get("menu.paused"), items),
+            choice,
+            transition: None,
+        }
+    }
+
+    fn close(&mut self, choice: PauseChoice) {
+        self.choice.set(Some(choice));
+        self.transition = Some(geng::state::Transition::Pop);
+    }
+}
+
+impl geng::State for PauseMenu {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(Rgba::BLACK), None, None);
+        let size = framebuffer.size().map(|x| x as f32);
+        self.context.geng.draw2d().textured_quad(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            Aabb2::ZERO.extend_positive(size),
+            &self.background,
+            Rgba::new(0.4, 0.4, 0.4, 1.0),
+        );
+        let assets = self.context.assets();
+        self.menu
+            .draw(&self.context.geng, &assets.fonts.main, framebuffer);
+    }
+
+    fn handle_event(&mut self, event: geng::Event) {
+        if let geng::Event::KeyPress {
+            key: geng::Key::Escape,
+        } = event
+        {
+            self.close(PauseChoice::Resume);
+            return;
+        }
+        if let Some(choice) = self.menu.handle_event(&event) {
+            self.close(choice);
+        }
+    }
+
+    fn transition(&mut self) -> Option<geng::state::Transition> {
+        self.transition.take()
+    }
+}
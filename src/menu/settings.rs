@@ -0,0 +1,313 @@
+use super::*;
+use crate::{
+    context::Context,
+    layout::{Anchor, Layout},
+    locale::Language,
+    settings::{Control, PaletteKind, Settings, WINDOW_SIZES},
+};
+
+#[derive(Debug, Clone, Copy)]
+enum SettingsItem {
+    MasterVolume,
+    SfxVolume,
+    MusicVolume,
+    Muted,
+    Language,
+    Fullscreen,
+    WindowSize,
+    AnimationSpeed,
+    Palette,
+    HighContrast,
+    TextScale,
+    ReducedMotion,
+    Captions,
+    Key(Control),
+    Back,
+}
+
+const ANIMATION_SPEEDS: [f32; 5] = [0.5, 0.75, 1.0, 1.5, 2.0];
+const TEXT_SCALES: [f32; 4] = [1.0, 1.25, 1.5, 2.0];
+
+pub struct SettingsScreen {
+    context: Rc<Context>,
+    menu: MenuList<SettingsItem>,
+    /// Control waiting for the next key press to be bound to it.
+    rebinding: Option<Control>,
+    /// Last key refused for [Self::rebinding] because the game already uses it.
+    reserved_key: Option<geng::Key>,
+    transition: Option<geng::state::Transition>,
+}
+
+impl SettingsScreen {
+    pub fn new(context: &Rc<Context>) -> Self {
+        let items = [
+            SettingsItem::MasterVolume,
+            SettingsItem::SfxVolume,
+            SettingsItem::MusicVolume,
+            SettingsItem::Muted,
+            SettingsItem::Language,
+            SettingsItem::Fullscreen,
+            SettingsItem::WindowSize,
+            SettingsItem::AnimationSpeed,
+            SettingsItem::Palette,
+            SettingsItem::HighContrast,
+            SettingsItem::TextScale,
+            SettingsItem::ReducedMotion,
+            SettingsItem::Captions,
+        ]
+        .into_iter()
+        .chain(Control::ALL.into_iter().map(SettingsItem::Key))
+        .chain([SettingsItem::Back])
+        .map(|item| MenuItem::new(item, ""))
+        .collect();
+        let mut screen = Self {
+            context: context.clone(),
+            menu: MenuList::new(context.locale.get("settings.title"), items),
+            rebinding: None,
+            reserved_key: None,
+            transition: None,
+        };
+        screen.update_labels();
+        screen
+    }
+
+    fn update_labels(&mut self) {
+        let settings = self.context.settings.borrow();
+        for index in 0..self.menu.items.len() {
+            let item = self.menu.items[index].action;
+            self.menu.items[index].label = self.label(&settings, item);
+        }
+    }
+
+    fn label(&self, settings: &Settings, item: SettingsItem) -> String {
+        let locale = &self.context.locale;
+        let percent = |volume: f32| format!("{}%", (volume * 100.0).round());
+        let on_off = |value: bool| locale.get(if value { "settings.on" } else { "settings.off" });
+        let (key, value) = match item {
+            SettingsItem::MasterVolume => (
+                "settings.master_volume",
+                percent(settings.audio.master_volume),
+            ),
+            SettingsItem::SfxVolume => ("settings.sfx_volume", percent(settings.audio.sfx_volume)),
+            SettingsItem::MusicVolume => (
+                "settings.music_volume",
+                percent(settings.audio.music_volume),
+            ),
+            SettingsItem::Muted => ("settings.sound", on_off(!settings.audio.muted).to_string()),
+            SettingsItem::Language => {
+                let chosen = locale.get(language_key(settings.language));
+                // The strings in use only change after a restart.
+                let value = if settings.language == locale.language {
+                    chosen.to_string()
+                } else {
+                    locale.format(
+                        "settings.language_pending",
+                        &[
+                            ("chosen", &chosen),
+                            ("current", &locale.get(language_key(locale.language))),
+                        ],
+                    )
+                };
+                ("settings.language", value)
+            }
+            SettingsItem::Fullscreen => (
+                "settings.fullscreen",
+                on_off(settings.fullscreen).to_string(),
+            ),
+            SettingsItem::WindowSize => (
+                "settings.window_size",
+                format!("{}×{}", settings.window_size.x, settings.window_size.y),
+            ),
+            SettingsItem::AnimationSpeed => (
+                "settings.animation_speed",
+                format!("{}×", settings.animation_speed),
+            ),
+            SettingsItem::Palette => (
+                "settings.palette",
+                locale.get(palette_key(settings.palette)).to_string(),
+            ),
+            SettingsItem::HighContrast => (
+                "settings.high_contrast",
+                on_off(settings.high_contrast).to_string(),
+            ),
+            SettingsItem::TextScale => (
+                "settings.text_scale",
+                format!("{}%", (settings.text_scale * 100.0).round()),
+            ),
+            SettingsItem::ReducedMotion => (
+                "settings.reduced_motion",
+                on_off(settings.reduced_motion).to_string(),
+            ),
+            SettingsItem::Captions => ("settings.captions", on_off(settings.captions).to_string()),
+            SettingsItem::Key(control) => {
+                let name = locale.get(control_key(control));
+                if self.rebinding == Some(control) {
+                    if let Some(key) = self.reserved_key {
+                        let key = format!("{key:?}");
+                        return locale.format(
+                            "settings.key_reserved",
+                            &[("control", &name), ("key", &key)],
+                        );
+                    }
+                    return locale.format("settings.press_key", &[("control", &name)]);
+                }
+                let keys = settings
+                    .keys
+                    .get(control)
+                    .iter()
+                    .map(|key| format!("{key:?}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return locale.format("settings.key", &[("control", &name), ("keys", &keys)]);
+            }
+            SettingsItem::Back => return locale.get("menu.back").to_string(),
+        };
+        locale.format(key, &[("value", &value)])
+    }
+
+    /// Changes a setting by `step` positions; activating an entry steps forward.
+    fn adjust(&mut self, item: SettingsItem, step: i32) {
+        let mut settings = self.context.settings.borrow_mut();
+        let volume = |volume: &mut f32| *volume = (*volume + 0.1 * step as f32).clamp(0.0, 1.0);
+        match item {
+            SettingsItem::MasterVolume => volume(&mut settings.audio.master_volume),
+            SettingsItem::SfxVolume => volume(&mut settings.audio.sfx_volume),
+            SettingsItem::MusicVolume => volume(&mut settings.audio.music_volume),
+            SettingsItem::Muted => settings.audio.muted = !settings.audio.muted,
+            SettingsItem::Language => {
+                settings.language = cycle(&[Language::En, Language::Ru], settings.language, step);
+            }
+            SettingsItem::Fullscreen => {
+                settings.fullscreen = !settings.fullscreen;
+                self.context
+                    .geng
+                    .window()
+                    .set_fullscreen(settings.fullscreen);
+            }
+            SettingsItem::WindowSize => {
+                let sizes = WINDOW_SIZES.map(|(width, height)| vec2(width, height));
+                settings.window_size = cycle(&sizes, settings.window_size, step);
+            }
+            SettingsItem::AnimationSpeed => {
+                settings.animation_speed = cycle(&ANIMATION_SPEEDS, settings.animation_speed, step);
+            }
+            SettingsItem::Palette => {
+                settings.palette = cycle(&PaletteKind::ALL, settings.palette, step);
+            }
+            SettingsItem::HighContrast => settings.high_contrast = !settings.high_contrast,
+            SettingsItem::TextScale => {
+                settings.text_scale = cycle(&TEXT_SCALES, settings.text_scale, step);
+            }
+            SettingsItem::ReducedMotion => settings.reduced_motion = !settings.reduced_motion,
+            SettingsItem::Captions => settings.captions = !settings.captions,
+            SettingsItem::Key(control) => {
+                if step > 0 {
+                    self.rebinding = Some(control);
+                }
+            }
+            SettingsItem::Back => {}
+        }
+        settings.save();
+    }
+}
+
+/// Steps through `values`, wrapping around. Unknown values start from the first one.
+fn cycle<T: Copy + PartialEq>(values: &[T], current: T, step: i32) -> T {
+    let index = values
+        .iter()
+        .position(|value| *value == current)
+        .unwrap_or(0);
+    let index = (index as i32 + step).rem_euclid(values.len() as i32);
+    values[index as usize]
+}
+
+fn language_key(language: Language) -> &'static str {
+    match language {
+        Language::En => "settings.language_en",
+        Language::Ru => "settings.language_ru",
+    }
+}
+
+fn palette_key(palette: PaletteKind) -> &'static str {
+    match palette {
+        PaletteKind::Default => "palette.default",
+        PaletteKind::Deuteranopia => "palette.deuteranopia",
+        PaletteKind::Protanopia => "palette.protanopia",
+        PaletteKind::Tritanopia => "palette.tritanopia",
+    }
+}
+
+fn control_key(control: Control) -> &'static str {
+    match control {
+        Control::MoveUp => "control.move_up",
+        Control::MoveDown => "control.move_down",
+        Control::MoveLeft => "control.move_left",
+        Control::MoveRight => "control.move_right",
+        Control::Pause => "control.pause",
+        Control::Mute => "control.mute",
+        Control::HighScores => "control.high_scores",
+        Control::Restart => "control.restart",
+    }
+}
+
+impl geng::State for SettingsScreen {
+    fn draw(&mut self, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(Rgba::BLACK), None, None);
+        let assets = self.context.assets();
+        let layout = Layout::new(framebuffer.size());
+        self.menu
+            .draw(&self.context.geng, &assets.fonts.main, framebuffer);
+        assets.fonts.main.draw(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            self.context.locale.get("settings.restart_hint"),
+            vec2(TextAlign::CENTER, TextAlign::BOTTOM),
+            mat3::translate(layout.point(Anchor::Bottom, vec2(0.0, 0.0)))
+                * mat3::scale_uniform(layout.px(18.0)),
+            Rgba::new(0.7, 0.7, 0.7, 1.0),
+        );
+    }
+
+    fn handle_event(&mut self, event: geng::Event) {
+        if let Some(control) = self.rebinding {
+            if let geng::Event::KeyPress { key } = event {
+                self.reserved_key = None;
+                if key != geng::Key::Escape {
+                    let mut settings = self.context.settings.borrow_mut();
+                    if settings.keys.rebind(control, key) {
+                        settings.save();
+                    } else {
+                        // Keep waiting for a key the game does not use already.
+                        self.reserved_key = Some(key);
+                    }
+                }
+                if self.reserved_key.is_none() {
+                    self.rebinding = None;
+                }
+                self.update_labels();
+            }
+            return;
+        }
+
+        if let geng::Event::KeyPress {
+            key: geng::Key::Escape,
+        } = event
+        {
+            self.transition = Some(geng::state::Transition::Pop);
+            return;
+        }
+        if let Some((item, step)) = self.menu.adjustment(&event) {
+            self.adjust(item, step);
+        } else if let Some(item) = self.menu.handle_event(&event) {
+            match item {
+                SettingsItem::Back => self.transition = Some(geng::state::Transition::Pop),
+                item => self.adjust(item, 1),
+            }
+        }
+        self.update_labels();
+    }
+
+    fn transition(&mut self) -> Option<geng::state::Transition> {
+        self.transition.take()
+    }
+}
@@ -0,0 +1,91 @@
+use super::*;
+
+pub enum Brew {
+    Heal(HP),
+    ExtraTurns(usize),
+    Status(Status),
+    Area {
+        radius: i64,
+        damage: DP,
+        status: Option<Status>,
+    },
+}
+
+impl Brew {
+    pub fn needs_target(&self) -> bool {
+        matches!(self, Brew::Area { .. })
+    }
+}
+
+impl Ingredient {
+    pub fn brew(&self) -> Brew {
+        match self {
+            Ingredient::Leaf => Brew::Heal(20),
+            Ingredient::Ice => Brew::Area {
+                radius: 1,
+                damage: 0,
+                status: Some(Status {
+                    kind: StatusKind::Frozen,
+                    turns: 2,
+                }),
+            },
+            Ingredient::Branch => Brew::Area {
+                radius: 0,
+                damage: 2,
+                status: None,
+            },
+            Ingredient::Banana => Brew::ExtraTurns(2),
+            Ingredient::Cherry => Brew::Area {
+                radius: 1,
+                damage: 1,
+                status: Some(Status {
+                    kind: StatusKind::Burning,
+                    turns: 2,
+                }),
+            },
+            Ingredient::Blueberry => Brew::Status(Status {
+                kind: StatusKind::Shielded,
+                turns: 3,
+            }),
+            Ingredient::Strawberry => Brew::Status(Status {
+                kind: StatusKind::Hasted,
+                turns: 3,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn healing_is_capped_at_max_health() {
+        let mut model = Model::new(0);
+        model.player.health = model.player.max_health - 5;
+        model.player.backpack.ingredients = vec![Ingredient::Leaf];
+
+        model.player_input(Action::UseItem(0));
+        assert_eq!(model.player.health, model.player.max_health);
+        assert!(model.player.backpack.ingredients.is_empty());
+        assert_eq!(model.stats.potions_brewed, 1);
+    }
+
+    #[test]
+    fn area_brews_wait_for_a_target() {
+        let mut model = Model::new(0);
+        let enemy = model.level_map.enemies()[0].id;
+        let target = model.level_map.enemies()[0].pos;
+        model.player.backpack.ingredients = vec![Ingredient::Ice];
+
+        model.player_input(Action::UseItem(0));
+        assert_eq!(model.targeting, Some(0));
+        assert_eq!(model.player.backpack.ingredients.len(), 1);
+
+        model.player_input(Action::MoveTo(target));
+        assert_eq!(model.targeting, None);
+        assert!(model.player.backpack.ingredients.is_empty());
+        let enemy = model.level_map.enemy(enemy).unwrap();
+        assert!(enemy.statuses.has(StatusKind::Frozen));
+    }
+}
@@ -0,0 +1,44 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnemyKind {
+    Scout,
+    Brute,
+    Frost,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EnemySpec {
+    pub kind: EnemyKind,
+    pub bonus_health: HP,
+}
+
+/// Number of enemies spawned after each map expansion.
+pub fn enemy_count(day: u64, map_cells: usize) -> usize {
+    1 + (day / 3) as usize + map_cells / 12
+}
+
+fn enemy_kind_weights(day: u64) -> [(EnemyKind, u32); 3] {
+    [
+        (EnemyKind::Scout, 6),
+        (
+            EnemyKind::Brute,
+            if day >= 3 { 2 + day as u32 / 3 } else { 0 },
+        ),
+        (
+            EnemyKind::Frost,
+            if day >= 5 { 1 + day as u32 / 5 } else { 0 },
+        ),
+    ]
+}
+
+pub fn enemy_spec(day: u64, rng: &mut impl Rng) -> EnemySpec {
+    let weights = enemy_kind_weights(day);
+    let kind = weights
+        .choose_weighted(rng, |(_, weight)| *weight)
+        .map_or(EnemyKind::Scout, |(kind, _)| *kind);
+    EnemySpec {
+        kind,
+        bonus_health: (day / 4) as HP,
+    }
+}
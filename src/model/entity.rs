@@ -0,0 +1,33 @@
+use super::*;
+
+/// Identifier of an enemy or item that stays the same for its whole life,
+/// unlike its index in the [LevelMap] lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntityId(u64);
+
+impl std::fmt::Display for EntityId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Hands out entity ids in increasing order.
+#[derive(Debug, Default)]
+pub struct IdGenerator {
+    next: u64,
+}
+
+impl IdGenerator {
+    pub fn next_id(&mut self) -> EntityId {
+        let id = EntityId(self.next);
+        self.next += 1;
+        id
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entity {
+    Player,
+    Enemy(EntityId),
+    Item(EntityId),
+}
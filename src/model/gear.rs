@@ -0,0 +1,149 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GearSlot {
+    Weapon,
+    Armour,
+    Trinket,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gear {
+    Dagger,
+    Axe,
+    LeatherArmour,
+    PlateArmour,
+    LuckyCharm,
+}
+
+impl Gear {
+    pub fn slot(&self) -> GearSlot {
+        match self {
+            Gear::Dagger | Gear::Axe => GearSlot::Weapon,
+            Gear::LeatherArmour | Gear::PlateArmour => GearSlot::Armour,
+            Gear::LuckyCharm => GearSlot::Trinket,
+        }
+    }
+
+    /// Bonus damage dealt by the player.
+    pub fn attack(&self) -> DP {
+        match self {
+            Gear::Dagger => 1,
+            Gear::Axe => 2,
+            Gear::LuckyCharm => 1,
+            Gear::LeatherArmour | Gear::PlateArmour => 0,
+        }
+    }
+
+    /// Damage absorbed from every hit the player takes.
+    pub fn defense(&self) -> DP {
+        match self {
+            Gear::LeatherArmour => 2,
+            Gear::PlateArmour => 5,
+            Gear::LuckyCharm => 1,
+            Gear::Dagger | Gear::Axe => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Equipment {
+    pub weapon: Option<Gear>,
+    pub armour: Option<Gear>,
+    pub trinket: Option<Gear>,
+}
+
+impl Equipment {
+    pub fn get(&self, slot: GearSlot) -> Option<Gear> {
+        match slot {
+            GearSlot::Weapon => self.weapon,
+            GearSlot::Armour => self.armour,
+            GearSlot::Trinket => self.trinket,
+        }
+    }
+
+    pub fn slot_mut(&mut self, slot: GearSlot) -> &mut Option<Gear> {
+        match slot {
+            GearSlot::Weapon => &mut self.weapon,
+            GearSlot::Armour => &mut self.armour,
+            GearSlot::Trinket => &mut self.trinket,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Gear> + '_ {
+        [self.weapon, self.armour, self.trinket]
+            .into_iter()
+            .flatten()
+    }
+
+    pub fn attack(&self) -> DP {
+        self.iter().map(|gear| gear.attack()).sum()
+    }
+
+    pub fn defense(&self) -> DP {
+        self.iter().map(|gear| gear.defense()).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equipment_stats_add_up() {
+        let equipment = Equipment {
+            weapon: Some(Gear::Axe),
+            armour: Some(Gear::PlateArmour),
+            trinket: Some(Gear::LuckyCharm),
+        };
+        assert_eq!(equipment.attack(), 3);
+        assert_eq!(equipment.defense(), 6);
+        assert_eq!(Equipment::default().attack(), 0);
+        assert_eq!(Equipment::default().defense(), 0);
+    }
+
+    #[test]
+    fn equipping_swaps_with_the_backpack() {
+        let mut model = Model::new(0);
+        model.player.backpack.gear = vec![Gear::Dagger, Gear::Axe, Gear::LeatherArmour];
+
+        model.player_input(Action::Equip(0));
+        assert_eq!(model.player.equipment.weapon, Some(Gear::Dagger));
+        assert_eq!(model.player.backpack.gear, [Gear::Axe, Gear::LeatherArmour]);
+
+        // A second weapon goes into the same slot and the first one back to the backpack.
+        model.player_input(Action::Equip(0));
+        assert_eq!(model.player.equipment.weapon, Some(Gear::Axe));
+        assert_eq!(
+            model.player.backpack.gear,
+            [Gear::LeatherArmour, Gear::Dagger]
+        );
+
+        model.player_input(Action::Equip(0));
+        assert_eq!(model.player.equipment.armour, Some(Gear::LeatherArmour));
+
+        model.player_input(Action::Unequip(GearSlot::Weapon));
+        assert_eq!(model.player.equipment.weapon, None);
+        assert_eq!(model.player.backpack.gear, [Gear::Dagger, Gear::Axe]);
+
+        // Out of range and empty slots are ignored.
+        model.player_input(Action::Equip(5));
+        model.player_input(Action::Unequip(GearSlot::Trinket));
+        assert_eq!(model.player.backpack.gear, [Gear::Dagger, Gear::Axe]);
+    }
+
+    #[test]
+    fn gear_changes_damage_dealt_and_taken() {
+        let mut model = Model::new(0);
+        model.player.equipment.weapon = Some(Gear::Axe);
+        model.player.equipment.armour = Some(Gear::LeatherArmour);
+        assert_eq!(model.player.attack_damage(2), 4);
+
+        let health = model.player.health;
+        model.player.take_damage(5);
+        assert_eq!(model.player.health, health - 3);
+        // Armour never heals.
+        model.player.take_damage(1);
+        assert_eq!(model.player.health, health - 3);
+    }
+}
@@ -0,0 +1,78 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy)]
+enum Loot {
+    Nothing,
+    Ingredient,
+    Gear,
+    Potion,
+    Coins,
+}
+
+fn loot_weights(day: u64) -> [(Loot, u32); 5] {
+    [
+        (Loot::Nothing, 4),
+        (Loot::Ingredient, 4 + day as u32 / 2),
+        (Loot::Gear, 1 + day as u32 / 4),
+        (Loot::Potion, if day >= 2 { 2 } else { 0 }),
+        (Loot::Coins, 3),
+    ]
+}
+
+fn ingredient_weights(day: u64) -> [(Ingredient, u32); 7] {
+    [
+        (Ingredient::Leaf, 6),
+        (Ingredient::Branch, 4),
+        (Ingredient::Banana, 3),
+        (Ingredient::Ice, if day >= 2 { 3 } else { 0 }),
+        (Ingredient::Blueberry, if day >= 2 { 2 } else { 0 }),
+        (Ingredient::Cherry, if day >= 3 { 2 } else { 0 }),
+        (Ingredient::Strawberry, if day >= 4 { 2 } else { 0 }),
+    ]
+}
+
+fn gear_weights(day: u64) -> [(Gear, u32); 5] {
+    [
+        (Gear::Dagger, 4),
+        (Gear::LeatherArmour, 3),
+        (Gear::Axe, if day >= 3 { 2 } else { 0 }),
+        (Gear::LuckyCharm, if day >= 4 { 1 } else { 0 }),
+        (Gear::PlateArmour, if day >= 6 { 1 } else { 0 }),
+    ]
+}
+
+fn potion_weights() -> [(Potion, u32); 2] {
+    [(Potion::Haste, 1), (Potion::Shield, 1)]
+}
+
+fn terrain_weights(day: u64) -> [(Option<Terrain>, u32); 4] {
+    [
+        (None, 12),
+        (Some(Terrain::Ice), 2),
+        (Some(Terrain::Swamp), if day >= 2 { 2 } else { 0 }),
+        (Some(Terrain::Fire), if day >= 4 { 1 } else { 0 }),
+    ]
+}
+
+fn choose<T: Copy>(weights: &[(T, u32)], rng: &mut impl Rng) -> Option<T> {
+    weights
+        .choose_weighted(rng, |(_, weight)| *weight)
+        .ok()
+        .map(|(value, _)| *value)
+}
+
+/// Rolls the item placed on a freshly expanded cell, if any.
+pub fn expansion_item(day: u64, rng: &mut impl Rng) -> Option<ItemKind> {
+    match choose(&loot_weights(day), rng)? {
+        Loot::Nothing => None,
+        Loot::Ingredient => choose(&ingredient_weights(day), rng).map(ItemKind::Ingredient),
+        Loot::Gear => choose(&gear_weights(day), rng).map(ItemKind::Gear),
+        Loot::Potion => choose(&potion_weights(), rng).map(ItemKind::Potion),
+        Loot::Coins => Some(ItemKind::Coins(rng.gen_range(1..=3 + day as Coins))),
+    }
+}
+
+/// Rolls the terrain of a freshly expanded cell, if any.
+pub fn expansion_terrain(day: u64, rng: &mut impl Rng) -> Option<Terrain> {
+    choose(&terrain_weights(day), rng).flatten()
+}
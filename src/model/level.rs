@@ -0,0 +1,29 @@
+use super::*;
+
+/// A starting setup selectable from the level select screen.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LevelPreset {
+    /// Locale key suffix for the level name, `level.<id>`.
+    pub id: String,
+    /// Fixed seed, so everyone playing the level gets the same run.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default = "default_start_day")]
+    pub start_day: u64,
+    #[serde(default)]
+    pub coins: Option<Coins>,
+}
+
+fn default_start_day() -> u64 {
+    1
+}
+
+impl LevelPreset {
+    pub fn apply(&self, model: &mut Model) {
+        model.day = self.start_day;
+        model.start_day = self.start_day;
+        if let Some(coins) = self.coins {
+            model.player.backpack.coins = coins;
+        }
+    }
+}
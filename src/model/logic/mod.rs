@@ -4,43 +4,105 @@ use super::*;
 
 impl Model {
     pub fn update(&mut self, delta_time: Time) {
-        for enemy in &mut self.level_map.enemies {
-            enemy.animation_clock += delta_time;
-            if let EnemyState::Action(Cooldown {
-                action, leftover, ..
-            }) = &mut enemy.state
-            {
-                *leftover -= delta_time;
-                if *leftover <= r32(0.0) {
-                    enemy.state = EnemyState::Idle;
-                    enemy.animation_clock = r32(0.0);
-                }
+        self.timeline.time += delta_time;
+        while let Some(event) = self.timeline.pop_due() {
+            self.handle_timed_event(event);
+        }
+    }
+
+    fn handle_timed_event(&mut self, event: TimedEvent) {
+        match event {
+            TimedEvent::SettleEnemies => self.settle_enemies(),
+            TimedEvent::NightStep => self.night_step(),
+            TimedEvent::SpawnEnemy(spec) => self.spawn_enemy(spec),
+            TimedEvent::ExpirePopups => {
+                let now = self.now();
+                self.popups.retain(|popup| !popup.lifetime.finished(now));
             }
         }
+    }
+
+    fn spawn_popup(&mut self, pos: vec2<i64>, kind: PopupKind) {
+        let lifetime = Cooldown::new((), self.now(), r32(0.8));
+        self.timeline
+            .schedule_at(lifetime.end(), TimedEvent::ExpirePopups);
+        self.popups.push(Popup {
+            pos: pos.map(|x| x as f32),
+            kind,
+            lifetime,
+        });
+    }
 
-        for enemy in &mut self.level_map.dead_enemies {
-            enemy.animation_clock += delta_time;
-            if let EnemyState::Action(Cooldown {
-                action, leftover, ..
-            }) = &mut enemy.state
-            {
-                *leftover -= delta_time;
+    fn settle_enemies(&mut self) {
+        let now = self.now();
+        for enemy in self.level_map.enemies_mut() {
+            if let EnemyState::Action(cooldown) = &enemy.state {
+                if cooldown.finished(now) {
+                    enemy.animation_start = cooldown.end();
+                    enemy.state = EnemyState::Idle;
+                }
             }
         }
         self.level_map
             .dead_enemies
-            .retain(|enemy| match enemy.state {
+            .retain(|enemy| match &enemy.state {
                 EnemyState::Idle => false,
-                EnemyState::Action(Cooldown { leftover, .. }) => leftover > r32(0.0),
+                EnemyState::Action(cooldown) => !cooldown.finished(now),
+            });
+    }
+
+    fn start_enemy_action(&mut self, id: EntityId, action: EnemyAction, duration: Time) {
+        let now = self.now();
+        if let Some(enemy) = self.level_map.enemy_mut(id) {
+            let end = enemy.start_action(action, now, duration);
+            self.timeline.schedule_at(end, TimedEvent::SettleEnemies);
+        }
+    }
+
+    fn spawn_enemy(&mut self, spec: EnemySpec) {
+        self.pending_spawns = self.pending_spawns.saturating_sub(1);
+        let Some(cell) = self
+            .level_map
+            .cells_iter()
+            .filter(|cell| {
+                !(*cell == self.player.pos
+                    || self.level_map.has_item_at(*cell)
+                    || self.level_map.has_enemy_at(*cell))
             })
+            .choose(&mut self.rng)
+        else {
+            return;
+        };
+        let id = self.level_map.add_enemy(cell, spec.kind, self.now());
+        let enemy = self.level_map.enemy_mut(id).expect("enemy was added above");
+        enemy.health += spec.bonus_health;
+        if let EnemyState::Action(cooldown) = &enemy.state {
+            self.timeline
+                .schedule_at(cooldown.end(), TimedEvent::SettleEnemies);
+        }
+        self.play_sound(SoundKind::RobotMove, Some(Entity::Enemy(id)));
+    }
+
+    fn play_sound(&mut self, sound: SoundKind, source: Option<Entity>) {
+        self.effects.push(Effect::PlaySound(sound, source));
     }
 
     pub fn player_input(&mut self, action: Action) {
+        self.handle_player_input(action);
+        if self.validate_inputs {
+            for violation in self.validate() {
+                log::error!("invariant violated after {action:?}: {violation}");
+            }
+        }
+    }
+
+    fn handle_player_input(&mut self, action: Action) {
         match self.state {
             State::Day => self.player_input_day(action),
             State::ExpandMap => self.player_input_expand_map(action),
             State::Shop => todo!(),
-            State::Night => self.player_input_night(action),
+            State::Night(_) => self.player_input_night(action),
+            State::GameOver => {}
         }
     }
 
@@ -50,36 +112,74 @@ impl Model {
                 if !self.level_map.adjacent(pos) {
                     return;
                 }
-                self.level_map.expansion_cells.push(pos);
+                self.level_map.expand(pos);
+                if let Some(kind) = expansion_item(self.day, &mut self.rng) {
+                    self.level_map.add_item(pos, kind);
+                }
+                if let Some(terrain) = expansion_terrain(self.day, &mut self.rng) {
+                    self.level_map.terrain.insert(pos, terrain);
+                }
                 self.phase_day();
 
-                if let Some(cell) = self
-                    .level_map
-                    .cells_iter()
-                    .filter(|cell| {
-                        !(*cell == self.player.pos
-                            || self.level_map.items.iter().any(|item| *cell == item.pos))
-                    })
-                    .choose(&mut thread_rng())
-                {
-                    self.level_map.enemies.push(Enemy::new(cell));
-                    self.effects.push(Effect::PlaySound(SoundKind::RobotMove));
+                let count = enemy_count(self.day, self.level_map.cells_iter().count());
+                for i in 0..count {
+                    let spec = enemy_spec(self.day, &mut self.rng);
+                    self.pending_spawns += 1;
+                    self.timeline
+                        .schedule(r32(0.3 * (i + 1) as f32), TimedEvent::SpawnEnemy(spec));
                 }
             }
             Action::MoveUp => {}
             Action::MoveDown => {}
             Action::MoveLeft => {}
             Action::MoveRight => {}
+            Action::UseItem(_) => {}
+            Action::Cancel => {}
+            Action::Equip(_) => {}
+            Action::Unequip(_) => {}
         }
     }
 
     pub fn player_input_day(&mut self, action: Action) {
+        match action {
+            Action::UseItem(slot) => {
+                self.use_item(slot);
+                return;
+            }
+            Action::Cancel => {
+                self.targeting = None;
+                return;
+            }
+            Action::Equip(index) => {
+                self.equip(index);
+                return;
+            }
+            Action::Unequip(slot) => {
+                self.unequip(slot);
+                return;
+            }
+            _ => {}
+        }
+
+        if let Some(slot) = self.targeting {
+            if let Action::MoveTo(target) = action {
+                self.use_item_at(slot, target);
+            }
+            return;
+        }
+
+        if self.player.statuses.has(StatusKind::Frozen) {
+            self.end_player_turn();
+            return;
+        }
+
         let delta = match action {
             Action::MoveDown => vec2(0, -1),
             Action::MoveUp => vec2(0, 1),
             Action::MoveLeft => vec2(-1, 0),
             Action::MoveRight => vec2(1, 0),
             Action::MoveTo(pos) => pos - self.player.pos,
+            Action::UseItem(_) | Action::Cancel | Action::Equip(_) | Action::Unequip(_) => return,
         };
         let target_pos = self.player.pos + delta;
 
@@ -91,103 +191,418 @@ impl Model {
             return;
         }
 
-        let target_on_enemy = self
-            .level_map
-            .enemies
-            .iter()
-            .any(|enemy| enemy.pos == target_pos);
-        if target_on_enemy {
+        if self.level_map.has_enemy_at(target_pos) {
             return;
         }
 
-        let (target_items, other_items) = self
-            .level_map
-            .items
-            .drain(..)
-            .partition(|item| item.pos == target_pos);
-        self.level_map.items = other_items;
         let mut did_hit_enemy = false;
-        for mut item in target_items {
-            match item.kind {
+        for id in self.level_map.items_at(target_pos) {
+            let Some(item) = self.level_map.item(id) else {
+                continue;
+            };
+            let kind = item.kind;
+            if !matches!(kind, ItemKind::Sword { .. }) {
+                self.level_map.remove_item(id);
+            }
+            match kind {
                 ItemKind::Sword { damage } => {
-                    for enemy in &mut self.level_map.enemies {
-                        enemy.take_damage(damage);
+                    let damage = self.player.attack_damage(damage);
+                    let now = self.now();
+                    for enemy in self.level_map.enemies_mut() {
+                        let end = enemy.take_damage(damage, now);
+                        self.timeline.schedule_at(end, TimedEvent::SettleEnemies);
                         did_hit_enemy = true;
                     }
-                    let (live, dead) = self
-                        .level_map
-                        .enemies
-                        .drain(..)
-                        .partition(|enemy| enemy.health > 0);
-                    self.level_map.enemies = live;
-                    self.level_map.dead_enemies = dead;
-
-                    item.pos = self.player.pos;
-                    self.level_map.items.push(item);
+                    self.remove_dead_enemies();
+
+                    // The sword stays behind, where the player stepped from.
+                    self.level_map.move_item(id, self.player.pos);
                 }
                 ItemKind::Ingredient(ingredient) => {
                     self.player.backpack.ingredients.push(ingredient)
                 }
+                ItemKind::Potion(potion) => {
+                    self.player.statuses.apply(potion.status());
+                    self.play_sound(SoundKind::PourWater, Some(Entity::Player));
+                }
+                ItemKind::Gear(gear) => self.player.backpack.gear.push(gear),
+                ItemKind::Coins(coins) => {
+                    self.player.backpack.coins += coins;
+                    self.play_sound(SoundKind::CoinPickup, Some(Entity::Player));
+                    self.spawn_popup(target_pos, PopupKind::Coins(coins));
+                }
             }
         }
 
         self.player.pos = target_pos;
         self.camera.center = self.player.pos.map(|x| x as f32);
 
-        self.effects.push(Effect::PlaySound(SoundKind::TwoSteps));
+        self.play_sound(SoundKind::TwoSteps, Some(Entity::Player));
         if did_hit_enemy {
-            self.effects.push(Effect::PlaySound(SoundKind::MetalHit));
+            self.play_sound(SoundKind::MetalHit, None);
         }
 
-        if self.level_map.enemies.is_empty() {
-            self.state = State::ExpandMap;
+        self.check_day_cleared();
+
+        self.end_player_turn();
+        // Entering the cell happens after this turn's statuses have ticked,
+        // so that a status like Frozen lasts into the next turn.
+        if let Some(terrain) = self.level_map.terrain.get(&target_pos) {
+            self.player.statuses.apply(terrain.status());
+        }
+    }
+
+    fn equip(&mut self, index: usize) {
+        if index >= self.player.backpack.gear.len() {
+            return;
+        }
+        let gear = self.player.backpack.gear.remove(index);
+        let equipped = self.player.equipment.slot_mut(gear.slot()).replace(gear);
+        self.player.backpack.gear.extend(equipped);
+    }
+
+    fn unequip(&mut self, slot: GearSlot) {
+        if let Some(gear) = self.player.equipment.slot_mut(slot).take() {
+            self.player.backpack.gear.push(gear);
+        }
+    }
+
+    fn use_item(&mut self, slot: usize) {
+        let Some(ingredient) = self.player.backpack.ingredients.get(slot) else {
+            return;
+        };
+        if ingredient.brew().needs_target() {
+            self.targeting = Some(slot);
+            return;
+        }
+        self.targeting = None;
+        let brew = self.player.backpack.ingredients.remove(slot).brew();
+        self.apply_brew(brew, self.player.pos);
+    }
+
+    fn use_item_at(&mut self, slot: usize, target: vec2<i64>) {
+        if !self.level_map.inside(target) || slot >= self.player.backpack.ingredients.len() {
+            return;
+        }
+        self.targeting = None;
+        let brew = self.player.backpack.ingredients.remove(slot).brew();
+        self.apply_brew(brew, target);
+    }
+
+    fn apply_brew(&mut self, brew: Brew, target: vec2<i64>) {
+        self.stats.potions_brewed += 1;
+        self.play_sound(SoundKind::PourWater, Some(Entity::Player));
+        match brew {
+            Brew::Heal(health) => {
+                self.player.health = (self.player.health + health).min(self.player.max_health);
+            }
+            Brew::ExtraTurns(turns) => self.player.turns_remaining += turns,
+            Brew::Status(status) => self.player.statuses.apply(status),
+            Brew::Area {
+                radius,
+                damage,
+                status,
+            } => {
+                let now = self.now();
+                let mut did_hit_enemy = false;
+                for enemy in self.level_map.enemies_mut() {
+                    if manhattan_dist(enemy.pos, target) > radius {
+                        continue;
+                    }
+                    if damage > 0 {
+                        let end = enemy.take_damage(damage, now);
+                        self.timeline.schedule_at(end, TimedEvent::SettleEnemies);
+                        did_hit_enemy = true;
+                    }
+                    if let Some(status) = status {
+                        enemy.statuses.apply(status);
+                    }
+                }
+                self.remove_dead_enemies();
+                if did_hit_enemy {
+                    self.play_sound(SoundKind::MetalHit, None);
+                }
+                self.check_day_cleared();
+            }
         }
+    }
 
-        self.player.turns_remaining = self.player.turns_remaining.saturating_sub(1);
+    fn end_player_turn(&mut self) {
+        self.stats.turns_taken += 1;
+        let hasted = self.player.statuses.has(StatusKind::Hasted);
+
+        let status_damage = self.player.statuses.damage_per_turn();
+        if status_damage > 0 {
+            self.player.take_damage(status_damage);
+        }
+        self.player.statuses.tick();
+        if self.check_game_over() {
+            return;
+        }
+
+        if !hasted {
+            self.player.turns_remaining = self.player.turns_remaining.saturating_sub(1);
+        }
         if self.player.turns_remaining == 0 {
             self.phase_night();
         }
     }
 
+    fn end_enemies_turn(&mut self) {
+        let now = self.now();
+        for enemy in self.level_map.enemies_mut() {
+            let status_damage = enemy.statuses.damage_per_turn();
+            if status_damage > 0 {
+                let end = enemy.take_damage(status_damage, now);
+                self.timeline.schedule_at(end, TimedEvent::SettleEnemies);
+            }
+            enemy.statuses.tick();
+        }
+        if let State::Night(night) = &mut self.state {
+            for (id, status) in std::mem::take(&mut night.entered_terrain) {
+                if let Some(enemy) = self.level_map.enemy_mut(id) {
+                    enemy.statuses.apply(status);
+                }
+            }
+        }
+        self.remove_dead_enemies();
+    }
+
+    fn remove_dead_enemies(&mut self) {
+        let dead = self.level_map.remove_dead_enemies();
+        self.stats.enemies_defeated += dead.len() as u64;
+        for enemy in &dead {
+            if enemy.loot > 0 {
+                self.level_map
+                    .add_item(enemy.pos, ItemKind::Coins(enemy.loot));
+            }
+        }
+        self.level_map.dead_enemies.extend(dead);
+    }
+
+    /// Lets the player expand the map once no enemies are left or on their way.
+    fn check_day_cleared(&mut self) {
+        if self.level_map.enemies().is_empty() && self.pending_spawns == 0 {
+            self.state = State::ExpandMap;
+        }
+    }
+
+    fn check_game_over(&mut self) -> bool {
+        if self.player.health > 0 {
+            return false;
+        }
+        self.state = State::GameOver;
+        true
+    }
+
     fn player_input_night(&mut self, _action: Action) {}
 
-    fn phase_day(&mut self) {
+    pub fn phase_day(&mut self) {
         self.state = State::Day;
         self.player.turns_remaining = 3;
     }
 
-    fn phase_night(&mut self) {
-        let mut rng = thread_rng();
-        self.state = State::Night;
+    pub fn phase_night(&mut self) {
+        let enemies = self.level_map.enemies();
+        let mut pending_enemies: VecDeque<EntityId> =
+            enemies.iter().map(|enemy| enemy.id).collect();
+        pending_enemies.extend(
+            enemies
+                .iter()
+                .filter(|enemy| enemy.statuses.has(StatusKind::Hasted))
+                .map(|enemy| enemy.id),
+        );
+        self.state = State::Night(Night {
+            step: Cooldown::new(NightStep::Dusk, self.now(), r32(0.5)),
+            pending_enemies,
+            entered_terrain: vec![],
+        });
+        self.timeline.schedule(r32(0.5), TimedEvent::NightStep);
+    }
 
-        let ids: Vec<_> = (0..self.level_map.enemies.len()).collect();
-        for id in ids {
-            let Some(enemy) = self.level_map.enemies.get(id) else {
-                continue;
-            };
+    fn set_night_step(&mut self, step: NightStep, duration: Time) {
+        let now = self.now();
+        if let State::Night(night) = &mut self.state {
+            night.step = Cooldown::new(step, now, duration);
+            self.timeline.schedule(duration, TimedEvent::NightStep);
+        }
+    }
 
-            let Some(target_position) = [vec2(-1, 0), vec2(0, -1), vec2(1, 0), vec2(0, 1)]
-                .into_iter()
-                .map(|delta| enemy.pos + delta)
-                .filter(|&pos| {
-                    self.level_map.inside(pos)
-                        && self.player.pos != pos
-                        && !self.level_map.enemies.iter().any(|other| other.pos == pos)
-                        && !self.level_map.items.iter().any(|item| item.pos == pos)
-                })
-                .choose(&mut rng)
-            else {
-                continue;
+    fn night_step(&mut self) {
+        let State::Night(night) = &self.state else {
+            return;
+        };
+        let step = night.step.action;
+        match step {
+            NightStep::Dusk | NightStep::EnemyMove => self.night_next_enemy(),
+            NightStep::Dawn => {
+                self.day += 1;
+                self.phase_day();
+            }
+        }
+    }
+
+    fn night_next_enemy(&mut self) {
+        loop {
+            let State::Night(night) = &mut self.state else {
+                return;
+            };
+            let Some(id) = night.pending_enemies.pop_front() else {
+                self.end_enemies_turn();
+                self.set_night_step(NightStep::Dawn, r32(0.5));
+                return;
             };
+            if self.night_enemy_turn(id) {
+                self.set_night_step(NightStep::EnemyMove, r32(0.4));
+                return;
+            }
+        }
+    }
+
+    fn night_enemy_turn(&mut self, id: EntityId) -> bool {
+        let Some(enemy) = self.level_map.enemy(id) else {
+            return false;
+        };
+        if enemy.statuses.has(StatusKind::Frozen) {
+            return false;
+        }
+        if enemy.attack_status.is_some() && manhattan_dist(enemy.pos, self.player.pos) == 1 {
+            self.night_enemy_attack(id);
+            return true;
+        }
+        self.night_move_enemy(id)
+    }
+
+    fn night_enemy_attack(&mut self, id: EntityId) {
+        let Some(enemy) = self.level_map.enemy(id) else {
+            return;
+        };
+        let Some(status) = enemy.attack_status else {
+            return;
+        };
+
+        self.start_enemy_action(id, EnemyAction::Attack, r32(0.4));
+        self.player.statuses.apply(status);
+        self.play_sound(SoundKind::MetalHit, Some(Entity::Enemy(id)));
+    }
+
+    fn night_move_enemy(&mut self, id: EntityId) -> bool {
+        let Some(enemy) = self.level_map.enemy(id) else {
+            return false;
+        };
 
-            let enemy = self
-                .level_map
-                .enemies
-                .get_mut(id)
-                .expect("enemy was confirmed to exist at the start of the loop");
-            enemy.pos = target_position;
+        let Some(target_position) = NEIGHBOURS
+            .into_iter()
+            .map(|(dx, dy)| enemy.pos + vec2(dx, dy))
+            .filter(|&pos| {
+                self.level_map.inside(pos)
+                    && self.player.pos != pos
+                    && !self.level_map.has_enemy_at(pos)
+                    && !self.level_map.has_item_at(pos)
+            })
+            .choose(&mut self.rng)
+        else {
+            return false;
+        };
+
+        let from = self
+            .level_map
+            .move_enemy(id, target_position)
+            .expect("enemy was confirmed to exist above");
+        self.start_enemy_action(id, EnemyAction::Move { from }, r32(0.4));
+        if let Some(terrain) = self.level_map.terrain.get(&target_position) {
+            if let State::Night(night) = &mut self.state {
+                night.entered_terrain.push((id, terrain.status()));
+            }
         }
+        self.play_sound(SoundKind::RobotMove, Some(Entity::Enemy(id)));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ice_freezes_the_player_for_one_turn() {
+        let mut model = Model::new(0);
+        let ice = vec2(0, 2);
+        model.level_map.terrain.insert(ice, Terrain::Ice);
+        model.player.pos = vec2(1, 2);
+
+        model.player_input(Action::MoveLeft);
+        assert_eq!(model.player.pos, ice);
+        assert!(model.player.statuses.has(StatusKind::Frozen));
+
+        model.player_input(Action::MoveRight);
+        assert_eq!(model.player.pos, ice, "a frozen player skips the turn");
+        assert!(!model.player.statuses.has(StatusKind::Frozen));
+
+        model.player_input(Action::MoveRight);
+        assert_eq!(model.player.pos, vec2(1, 2));
+        assert!(!model.player.statuses.has(StatusKind::Frozen));
+    }
+
+    #[test]
+    fn pending_spawns_keep_the_day_going() {
+        let mut model = Model::new(0);
+        // Stepping onto the sword finishes off the only enemy.
+        model.level_map.enemies_mut()[0].health = 1;
+        model.player_input(Action::MoveDown);
+        assert!(matches!(model.state, State::ExpandMap));
+
+        let pos = model.level_map.adjacent_cells_iter().next().unwrap();
+        model.player_input(Action::MoveTo(pos));
+        assert!(matches!(model.state, State::Day));
+        assert!(model.level_map.enemies().is_empty());
+        assert!(model.pending_spawns > 0);
+
+        // Nothing left to hit, but the spawns are still on their way.
+        model.player_input(Action::MoveUp);
+        assert!(matches!(model.state, State::Day));
+
+        model.update(r32(10.0));
+        assert_eq!(model.pending_spawns, 0);
+        assert!(!model.level_map.enemies().is_empty());
+    }
+
+    #[test]
+    fn shield_blocks_status_damage() {
+        let mut model = Model::new(0);
+        let health = model.player.health;
+        for kind in [StatusKind::Poisoned, StatusKind::Shielded] {
+            model.player.statuses.apply(Status { kind, turns: 2 });
+        }
+
+        model.player_input(Action::MoveUp);
+        assert_eq!(model.player.health, health);
+    }
+
+    #[test]
+    fn ice_freezes_an_enemy_for_one_night() {
+        let mut model = Model::new(0);
+        let ice = vec2(0, 2);
+        model.level_map.terrain.insert(ice, Terrain::Ice);
+        // Boxed in by the player and an item, the scout at (0, 1) can only step onto the ice.
+        model.player.pos = vec2(1, 1);
+        model.level_map.add_item(vec2(0, 0), ItemKind::Coins(1));
+        let id = model.level_map.enemies()[0].id;
+
+        model.phase_night();
+        assert!(model.night_move_enemy(id));
+        model.end_enemies_turn();
+        let enemy = model.level_map.enemy(id).unwrap();
+        assert_eq!(enemy.pos, ice);
+        assert!(enemy.statuses.has(StatusKind::Frozen));
 
-        self.phase_day();
+        model.phase_night();
+        assert!(
+            !model.night_enemy_turn(id),
+            "a frozen enemy skips the night"
+        );
+        model.end_enemies_turn();
+        let enemy = model.level_map.enemy(id).unwrap();
+        assert!(!enemy.statuses.has(StatusKind::Frozen));
     }
 }
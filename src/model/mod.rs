@@ -1,4 +1,27 @@
+mod brew;
+mod difficulty;
+mod entity;
+mod gear;
+mod generation;
+mod level;
 mod logic;
+mod occupancy;
+mod score;
+mod status;
+mod timeline;
+mod validate;
+
+pub use self::brew::*;
+pub use self::difficulty::*;
+pub use self::entity::*;
+pub use self::gear::*;
+pub use self::generation::*;
+pub use self::level::*;
+pub use self::occupancy::*;
+pub use self::score::*;
+pub use self::status::*;
+pub use self::timeline::*;
+pub use self::validate::*;
 
 use geng::prelude::*;
 use itertools::Itertools;
@@ -6,24 +29,56 @@ use log::Level;
 
 pub type Time = R32;
 
+#[derive(Debug, Clone, Copy)]
 pub enum Action {
     MoveUp,
     MoveDown,
     MoveLeft,
     MoveRight,
     MoveTo(vec2<i64>),
+    UseItem(usize),
+    Cancel,
+    Equip(usize),
+    Unequip(GearSlot),
 }
 
 pub struct Backpack {
     pub ingredients: Vec<Ingredient>,
+    pub gear: Vec<Gear>,
     pub coins: Coins,
 }
 
 pub struct Player {
     pub pos: vec2<i64>,
     pub health: HP,
+    pub max_health: HP,
     pub backpack: Backpack,
+    pub equipment: Equipment,
     pub turns_remaining: usize,
+    pub statuses: Statuses,
+}
+
+impl Player {
+    pub fn take_damage(&mut self, damage: DP) {
+        if self.statuses.has(StatusKind::Shielded) {
+            return;
+        }
+        self.health -= (damage - self.equipment.defense()).max(0);
+    }
+
+    pub fn attack_damage(&self, base_damage: DP) -> DP {
+        base_damage + self.equipment.attack()
+    }
+
+    pub fn animation(&self, now: Time) -> (&'static str, AnimationTime) {
+        ("player.idle", AnimationTime::Clock(now))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum AnimationTime {
+    Clock(Time),
+    Progress(Time),
 }
 
 type HP = i64;
@@ -32,6 +87,7 @@ type DP = i64;
 
 type Coins = i64;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Ingredient {
     Leaf,
     Ice,
@@ -42,95 +98,374 @@ pub enum Ingredient {
     Strawberry,
 }
 
+#[derive(Clone, Copy)]
 pub enum ItemKind {
     Sword { damage: DP },
     Ingredient(Ingredient),
+    Potion(Potion),
+    Gear(Gear),
+    Coins(Coins),
 }
 
 pub struct Item {
+    pub id: EntityId,
     pub pos: vec2<i64>,
     pub kind: ItemKind,
 }
 
 pub struct Enemy {
+    pub id: EntityId,
+    pub kind: EnemyKind,
     pub pos: vec2<i64>,
     pub health: HP,
     pub damage: DP,
     pub state: EnemyState,
     pub mode: EnemyMode,
-    pub animation_clock: Time,
+    pub animation_start: Time,
+    pub statuses: Statuses,
+    pub attack_status: Option<Status>,
+    pub loot: Coins,
 }
 
 impl Enemy {
-    pub fn new(pos: vec2<i64>) -> Self {
+    pub fn new(id: EntityId, pos: vec2<i64>, kind: EnemyKind, now: Time) -> Self {
+        let (health, damage, attack_status, loot) = match kind {
+            EnemyKind::Scout => (3, 0, None, 2),
+            EnemyKind::Brute => (6, 0, None, 4),
+            EnemyKind::Frost => (
+                4,
+                0,
+                Some(Status {
+                    kind: StatusKind::Frozen,
+                    turns: 1,
+                }),
+                3,
+            ),
+        };
         Self {
+            id,
+            kind,
             pos,
-            health: 3,
-            damage: 0,
-            state: EnemyState::Action(Cooldown::new(EnemyAction::Spawn, r32(0.5))),
+            health,
+            damage,
+            state: EnemyState::Action(Cooldown::new(EnemyAction::Spawn, now, r32(0.5))),
             mode: EnemyMode::Normal,
-            animation_clock: r32(0.0),
+            animation_start: now,
+            statuses: Statuses::default(),
+            attack_status,
+            loot,
         }
     }
 
-    pub fn take_damage(&mut self, damage: DP) {
-        self.health -= damage;
+    /// Starts a timed action and returns the time it finishes at.
+    pub fn start_action(&mut self, action: EnemyAction, now: Time, duration: Time) -> Time {
+        let cooldown = Cooldown::new(action, now, duration);
+        let end = cooldown.end();
+        self.state = EnemyState::Action(cooldown);
+        self.animation_start = now;
+        end
+    }
+
+    pub fn take_damage(&mut self, damage: DP, now: Time) -> Time {
+        if !self.statuses.has(StatusKind::Shielded) {
+            self.health -= damage;
+        }
+        self.mode = EnemyMode::Damaged;
         if self.health > 0 {
-            self.state = EnemyState::Action(Cooldown::new(EnemyAction::TakeDamage, r32(0.5)));
+            self.start_action(EnemyAction::TakeDamage, now, r32(0.5))
         } else {
-            self.state = EnemyState::Action(Cooldown::new(EnemyAction::Die, r32(0.5)));
+            self.start_action(EnemyAction::Die, now, r32(0.5))
+        }
+    }
+
+    pub fn render_pos(&self, now: Time) -> vec2<f32> {
+        let pos = self.pos.map(|x| x as f32);
+        match &self.state {
+            EnemyState::Action(cooldown) => match cooldown.action {
+                EnemyAction::Move { from } => {
+                    let from = from.map(|x| x as f32);
+                    from + (pos - from) * cooldown.progress(now).as_f32()
+                }
+                _ => pos,
+            },
+            EnemyState::Idle => pos,
+        }
+    }
+
+    pub fn animation(&self, now: Time) -> (&'static str, AnimationTime) {
+        match &self.state {
+            EnemyState::Idle => {
+                let key = match self.mode {
+                    EnemyMode::Normal => "enemy.idle_normal",
+                    EnemyMode::Damaged => "enemy.idle_damaged",
+                };
+                (key, AnimationTime::Clock(now - self.animation_start))
+            }
+            EnemyState::Action(cooldown) => {
+                let key = match cooldown.action {
+                    EnemyAction::TakeDamage => "enemy.take_damage",
+                    EnemyAction::Attack => "enemy.attack",
+                    EnemyAction::Die => "enemy.die",
+                    EnemyAction::Spawn => "enemy.spawn",
+                    EnemyAction::Move { .. } => "enemy.move",
+                };
+                (key, AnimationTime::Progress(cooldown.progress(now)))
+            }
         }
-        self.animation_clock = r32(0.0);
-        self.mode = EnemyMode::Damaged;
     }
 }
 
+#[derive(Debug)]
 pub enum EnemyState {
     Idle,
     Action(Cooldown<EnemyAction>),
 }
 
+#[derive(Debug)]
 pub struct Cooldown<T> {
     pub action: T,
-    pub leftover: Time,
+    pub start: Time,
     pub total: Time,
 }
 
 impl<T> Cooldown<T> {
-    pub fn new(action: T, total: Time) -> Self {
+    pub fn new(action: T, start: Time, total: Time) -> Self {
         Self {
             action,
+            start,
             total,
-            leftover: total,
         }
     }
+
+    pub fn end(&self) -> Time {
+        self.start + self.total
+    }
+
+    /// Time left until the cooldown finishes.
+    pub fn leftover(&self, now: Time) -> Time {
+        (self.end() - now).max(r32(0.0))
+    }
+
+    pub fn finished(&self, now: Time) -> bool {
+        now >= self.end()
+    }
+
+    pub fn progress(&self, now: Time) -> Time {
+        ((now - self.start) / self.total).clamp(r32(0.0), r32(1.0))
+    }
 }
 
+#[derive(Debug)]
 pub enum EnemyMode {
     Normal,
     Damaged,
 }
 
+#[derive(Debug)]
 pub enum EnemyAction {
     TakeDamage,
     Attack,
     Die,
     Spawn,
+    Move { from: vec2<i64> },
 }
 
+/// Offsets to the four cells sharing a side with a cell.
+pub const NEIGHBOURS: [(i64, i64); 4] = [(-1, 0), (0, -1), (1, 0), (0, 1)];
+/// The same offsets in the order [LevelMap::sweep_frontier] visits them.
+const EXPANSION_NEIGHBOURS: [(i64, i64); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
 pub struct LevelMap {
     pub size: vec2<i64>,
-    pub items: Vec<Item>,
-    pub enemies: Vec<Enemy>,
+    items: Vec<Item>,
+    enemies: Vec<Enemy>,
     pub dead_enemies: Vec<Enemy>,
-    pub expansion_cells: Vec<vec2<i64>>,
+    expansion_cells: Vec<vec2<i64>>,
+    pub terrain: HashMap<vec2<i64>, Terrain>,
+    occupancy: Occupancy,
+    /// Cells the map can be expanded to, updated as cells are added.
+    frontier: Vec<vec2<i64>>,
+    ids: IdGenerator,
+    /// Index of every item and enemy in its list, for lookups by id.
+    item_slots: HashMap<EntityId, usize>,
+    enemy_slots: HashMap<EntityId, usize>,
 }
 
 impl LevelMap {
+    pub fn new(size: vec2<i64>, terrain: HashMap<vec2<i64>, Terrain>) -> Self {
+        let mut level_map = Self {
+            size,
+            items: vec![],
+            enemies: vec![],
+            dead_enemies: vec![],
+            expansion_cells: vec![],
+            terrain,
+            occupancy: Occupancy::default(),
+            frontier: vec![],
+            ids: IdGenerator::default(),
+            item_slots: HashMap::new(),
+            enemy_slots: HashMap::new(),
+        };
+        level_map.frontier = level_map.sweep_frontier();
+        level_map
+    }
+
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    pub fn enemies(&self) -> &[Enemy] {
+        &self.enemies
+    }
+
+    pub fn item(&self, id: EntityId) -> Option<&Item> {
+        self.items.get(*self.item_slots.get(&id)?)
+    }
+
+    pub fn enemy(&self, id: EntityId) -> Option<&Enemy> {
+        self.enemies.get(*self.enemy_slots.get(&id)?)
+    }
+
+    /// See [LevelMap::enemies_mut] on changing the position.
+    pub fn enemy_mut(&mut self, id: EntityId) -> Option<&mut Enemy> {
+        self.enemies.get_mut(*self.enemy_slots.get(&id)?)
+    }
+
+    /// Enemies with everything but their position open for changes;
+    /// moving an enemy goes through [LevelMap::move_enemy].
+    pub fn enemies_mut(&mut self) -> &mut [Enemy] {
+        &mut self.enemies
+    }
+
+    pub fn expansion_cells(&self) -> &[vec2<i64>] {
+        &self.expansion_cells
+    }
+
+    pub fn has_enemy_at(&self, pos: vec2<i64>) -> bool {
+        self.occupancy.has_enemy(pos)
+    }
+
+    pub fn has_item_at(&self, pos: vec2<i64>) -> bool {
+        self.occupancy.has_item(pos)
+    }
+
+    pub fn add_item(&mut self, pos: vec2<i64>, kind: ItemKind) -> EntityId {
+        let id = self.ids.next_id();
+        self.occupancy.add_item(pos);
+        self.item_slots.insert(id, self.items.len());
+        self.items.push(Item { id, pos, kind });
+        id
+    }
+
+    /// Ids of the items lying at `pos`.
+    pub fn items_at(&self, pos: vec2<i64>) -> Vec<EntityId> {
+        if !self.has_item_at(pos) {
+            return vec![];
+        }
+        self.items
+            .iter()
+            .filter(|item| item.pos == pos)
+            .map(|item| item.id)
+            .collect()
+    }
+
+    pub fn remove_item(&mut self, id: EntityId) -> Option<Item> {
+        let index = self.item_slots.remove(&id)?;
+        let item = self.items.swap_remove(index);
+        if let Some(moved) = self.items.get(index) {
+            self.item_slots.insert(moved.id, index);
+        }
+        self.occupancy.remove_item(item.pos);
+        Some(item)
+    }
+
+    pub fn move_item(&mut self, id: EntityId, to: vec2<i64>) {
+        let Some(item) = self
+            .item_slots
+            .get(&id)
+            .and_then(|&index| self.items.get_mut(index))
+        else {
+            return;
+        };
+        let from = std::mem::replace(&mut item.pos, to);
+        self.occupancy.remove_item(from);
+        self.occupancy.add_item(to);
+    }
+
+    pub fn add_enemy(&mut self, pos: vec2<i64>, kind: EnemyKind, now: Time) -> EntityId {
+        let id = self.ids.next_id();
+        self.occupancy.add_enemy(pos);
+        self.enemy_slots.insert(id, self.enemies.len());
+        self.enemies.push(Enemy::new(id, pos, kind, now));
+        id
+    }
+
+    /// Moves an enemy to `to`, returning where it came from.
+    pub fn move_enemy(&mut self, id: EntityId, to: vec2<i64>) -> Option<vec2<i64>> {
+        let enemy = self.enemies.get_mut(*self.enemy_slots.get(&id)?)?;
+        let from = std::mem::replace(&mut enemy.pos, to);
+        self.occupancy.remove_enemy(from);
+        self.occupancy.add_enemy(to);
+        Some(from)
+    }
+
+    /// Removes enemies without health left and returns them.
+    pub fn remove_dead_enemies(&mut self) -> Vec<Enemy> {
+        let (live, dead): (Vec<_>, Vec<_>) =
+            self.enemies.drain(..).partition(|enemy| enemy.health > 0);
+        self.enemies = live;
+        for enemy in &dead {
+            self.occupancy.remove_enemy(enemy.pos);
+        }
+        if !dead.is_empty() {
+            self.enemy_slots = slots(&self.enemies, |enemy| enemy.id);
+        }
+        dead
+    }
+
+    pub fn expand(&mut self, pos: vec2<i64>) {
+        let newly_adjacent: Vec<_> = EXPANSION_NEIGHBOURS
+            .into_iter()
+            .map(|(dx, dy)| pos + vec2(dx, dy))
+            .filter(|&cell| !self.adjacent(cell))
+            .collect();
+        self.occupancy.add_expansion(pos);
+        self.expansion_cells.push(pos);
+
+        self.frontier.retain(|&cell| cell != pos);
+        for cell in newly_adjacent {
+            if self.adjacent(cell) {
+                self.insert_frontier(cell);
+            }
+        }
+    }
+
+    /// Keeps the frontier in the order [LevelMap::sweep_frontier] produces:
+    /// cells around the original map first, column by column, then the rest in the
+    /// order they became expandable.
+    fn insert_frontier(&mut self, cell: vec2<i64>) {
+        match self.sweep_rank(cell) {
+            Some(rank) => {
+                let index = self
+                    .frontier
+                    .partition_point(|&other| self.sweep_rank(other).is_some_and(|r| r < rank));
+                self.frontier.insert(index, cell);
+            }
+            None => self.frontier.push(cell),
+        }
+    }
+
+    /// Position of a cell in the sweep over the box around the original map, if it is in the box.
+    fn sweep_rank(&self, cell: vec2<i64>) -> Option<i64> {
+        let inside_box =
+            cell.x >= -1 && cell.x <= self.size.x && cell.y >= -1 && cell.y <= self.size.y;
+        inside_box.then(|| (cell.x + 1) * (self.size.y + 2) + cell.y + 1)
+    }
+
     pub fn inside(&self, pos: vec2<i64>) -> bool {
         let inside_original =
             pos.x >= 0 && pos.x < self.size.x && pos.y >= 0 && pos.y < self.size.y;
-        inside_original || self.expansion_cells.contains(&pos)
+        inside_original || self.occupancy.is_expansion(pos)
     }
 
     pub fn adjacent(&self, pos: vec2<i64>) -> bool {
@@ -139,10 +474,9 @@ impl LevelMap {
                 || pos.x >= 0 && pos.x < self.size.x && pos.y >= -1 && pos.y <= self.size.y;
         !self.inside(pos)
             && (inside_original_expansion
-                || self
-                    .expansion_cells
+                || NEIGHBOURS
                     .iter()
-                    .any(|cell| manhattan_dist(*cell, pos) == 1))
+                    .any(|&(dx, dy)| self.occupancy.is_expansion(pos + vec2(dx, dy))))
     }
 
     pub fn cells_iter(&self) -> impl Iterator<Item = vec2<i64>> + '_ {
@@ -152,6 +486,12 @@ impl LevelMap {
     }
 
     pub fn adjacent_cells_iter(&self) -> impl Iterator<Item = vec2<i64>> + '_ {
+        self.frontier.iter().copied()
+    }
+
+    /// Finds the frontier from scratch by checking the box around the original map
+    /// and the neighbours of every expansion cell.
+    pub fn sweep_frontier(&self) -> Vec<vec2<i64>> {
         (-1..=self.size.x)
             .flat_map(|x| (-1..=self.size.y).map(move |y| vec2(x, y)))
             .chain(self.expansion_cells.iter().flat_map(|cell| {
@@ -163,9 +503,18 @@ impl LevelMap {
             }))
             .unique()
             .filter(|cell| self.adjacent(*cell))
+            .collect()
     }
 }
 
+fn slots<T>(entities: &[T], id: impl Fn(&T) -> EntityId) -> HashMap<EntityId, usize> {
+    entities
+        .iter()
+        .enumerate()
+        .map(|(index, entity)| (id(entity), index))
+        .collect()
+}
+
 pub fn manhattan_dist(pos1: vec2<i64>, pos2: vec2<i64>) -> i64 {
     (pos1.x - pos2.x).abs() + (pos1.y - pos2.y).abs()
 }
@@ -175,10 +524,22 @@ pub enum SoundKind {
     MetalHit,
     PourWater,
     RobotMove,
+    CoinPickup,
 }
 
 pub enum Effect {
-    PlaySound(SoundKind),
+    /// A sound and the entity that made it, if any.
+    PlaySound(SoundKind, Option<Entity>),
+}
+
+pub struct Popup {
+    pub pos: vec2<f32>,
+    pub kind: PopupKind,
+    pub lifetime: Cooldown<()>,
+}
+
+pub enum PopupKind {
+    Coins(Coins),
 }
 
 pub struct Model {
@@ -186,18 +547,63 @@ pub struct Model {
     pub player: Player,
     pub level_map: LevelMap,
     pub effects: Vec<Effect>,
+    pub popups: Vec<Popup>,
     pub state: State,
+    pub day: u64,
+    /// Day the run started on; level presets may start later than day 1.
+    pub start_day: u64,
+    pub stats: RunStats,
+    pub timeline: Timeline,
+    /// Enemies scheduled to spawn that have not appeared yet.
+    pub pending_spawns: usize,
+    /// Seed the run was started with, so it can be replayed.
+    pub seed: u64,
+    pub rng: StdRng,
+    /// Backpack slot of an item waiting for the player to choose a target cell.
+    pub targeting: Option<usize>,
+    /// Checks [Model::validate] after every player input and logs violations.
+    pub validate_inputs: bool,
+    /// Set once a console command has changed the run, which keeps it off the high-score table.
+    pub tainted: bool,
 }
 
+#[derive(Debug)]
 pub enum State {
     Day,
     ExpandMap,
     Shop,
-    Night,
+    Night(Night),
+    GameOver,
+}
+
+#[derive(Debug)]
+pub struct Night {
+    pub step: Cooldown<NightStep>,
+    pub pending_enemies: VecDeque<EntityId>,
+    /// Statuses of the terrain enemies stepped onto, applied once the night's statuses have ticked.
+    pub entered_terrain: Vec<(EntityId, Status)>,
+}
+
+impl Night {
+    pub fn darkness(&self, now: Time) -> f32 {
+        let progress = self.step.progress(now).as_f32();
+        match self.step.action {
+            NightStep::Dusk => progress,
+            NightStep::EnemyMove => 1.0,
+            NightStep::Dawn => 1.0 - progress,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum NightStep {
+    Dusk,
+    EnemyMove,
+    Dawn,
 }
 
 impl Model {
-    pub fn new() -> Self {
+    pub fn new(seed: u64) -> Self {
         let mut model = Self {
             camera: Camera2d {
                 center: vec2(0.0, 0.0),
@@ -207,32 +613,134 @@ impl Model {
             player: Player {
                 pos: vec2(2, 1),
                 health: 100,
+                max_health: 100,
                 backpack: Backpack {
                     ingredients: vec![],
+                    gear: vec![],
                     coins: 10,
                 },
+                equipment: Equipment::default(),
                 turns_remaining: 3,
+                statuses: Statuses::default(),
             },
-            level_map: LevelMap {
-                size: vec2(3, 3),
-                items: vec![
-                    Item {
-                        pos: vec2(2, 0),
-                        kind: ItemKind::Sword { damage: 2 },
-                    },
-                    Item {
-                        pos: vec2(1, 2),
-                        kind: ItemKind::Ingredient(Ingredient::Leaf),
-                    },
-                ],
-                enemies: vec![Enemy::new(vec2(0, 1))],
-                dead_enemies: vec![],
-                expansion_cells: vec![],
-            },
+            level_map: LevelMap::new(vec2(3, 3), HashMap::new()),
             effects: vec![],
+            popups: vec![],
             state: State::Day,
+            day: 1,
+            start_day: 1,
+            stats: RunStats::default(),
+            timeline: Timeline::new(),
+            pending_spawns: 0,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            targeting: None,
+            validate_inputs: cfg!(debug_assertions),
+            tainted: false,
         };
+        let level_map = &mut model.level_map;
+        level_map.add_item(vec2(2, 0), ItemKind::Sword { damage: 2 });
+        level_map.add_item(vec2(1, 2), ItemKind::Ingredient(Ingredient::Leaf));
+        level_map.add_enemy(vec2(0, 1), EnemyKind::Scout, r32(0.0));
+        model.timeline.schedule(r32(0.5), TimedEvent::SettleEnemies);
         model.camera.center = model.player.pos.map(|x| x as f32);
         model
     }
+
+    pub fn now(&self) -> Time {
+        self.timeline.time
+    }
+
+    /// The player, enemy or item at a cell. Enemies take precedence over items below them.
+    pub fn entity_at(&self, pos: vec2<i64>) -> Option<Entity> {
+        if self.player.pos == pos {
+            return Some(Entity::Player);
+        }
+        if self.level_map.has_enemy_at(pos) {
+            let enemy = self
+                .level_map
+                .enemies()
+                .iter()
+                .find(|enemy| enemy.pos == pos)?;
+            return Some(Entity::Enemy(enemy.id));
+        }
+        self.level_map
+            .items_at(pos)
+            .first()
+            .map(|&id| Entity::Item(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frontier_matches_a_full_sweep() {
+        let mut level_map = LevelMap::new(vec2(3, 3), HashMap::new());
+        assert_eq!(
+            level_map.adjacent_cells_iter().collect::<Vec<_>>(),
+            level_map.sweep_frontier()
+        );
+        let expansions = [
+            vec2(3, 1),
+            vec2(4, 1),
+            vec2(3, 0),
+            vec2(3, -1),
+            vec2(-1, 1),
+            vec2(-2, 1),
+            vec2(5, 1),
+            vec2(4, 2),
+            vec2(1, 3),
+            vec2(1, 4),
+            vec2(0, 4),
+        ];
+        for pos in expansions {
+            assert!(level_map.adjacent(pos), "{pos:?} is not expandable");
+            level_map.expand(pos);
+            assert_eq!(
+                level_map.adjacent_cells_iter().collect::<Vec<_>>(),
+                level_map.sweep_frontier(),
+                "after expanding {pos:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn occupancy_matches_a_rebuild() {
+        let mut level_map = LevelMap::new(vec2(3, 3), HashMap::new());
+        let check = |level_map: &LevelMap, step: &str| {
+            assert_eq!(
+                Occupancy::build(level_map),
+                level_map.occupancy,
+                "after {step}"
+            );
+        };
+
+        let coins = level_map.add_item(vec2(1, 1), ItemKind::Coins(1));
+        let leaf = level_map.add_item(vec2(1, 1), ItemKind::Ingredient(Ingredient::Leaf));
+        let scout = level_map.add_enemy(vec2(0, 0), EnemyKind::Scout, r32(0.0));
+        let brute = level_map.add_enemy(vec2(2, 2), EnemyKind::Brute, r32(0.0));
+        level_map.expand(vec2(3, 1));
+        check(&level_map, "adding");
+
+        level_map.remove_item(coins);
+        assert!(level_map.has_item_at(vec2(1, 1)), "the leaf is still there");
+        check(&level_map, "removing one of two stacked items");
+
+        level_map.move_item(leaf, vec2(3, 1));
+        assert!(!level_map.has_item_at(vec2(1, 1)));
+        check(&level_map, "moving an item");
+
+        level_map.move_enemy(scout, vec2(0, 1));
+        assert!(!level_map.has_enemy_at(vec2(0, 0)));
+        check(&level_map, "moving an enemy");
+
+        level_map.enemy_mut(brute).unwrap().health = 0;
+        let dead = level_map.remove_dead_enemies();
+        assert_eq!(dead.len(), 1);
+        assert!(level_map.enemy(brute).is_none());
+        assert_eq!(level_map.enemy(scout).unwrap().pos, vec2(0, 1));
+        check(&level_map, "removing a dead enemy");
+    }
 }
@@ -0,0 +1,71 @@
+use super::*;
+
+/// Grid index of the cells taken by map expansions, enemies and items,
+/// so that occupancy checks do not scan the entity lists.
+///
+/// Kept in sync by the [LevelMap] methods that add, move and remove entities.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Occupancy {
+    expansion: HashSet<vec2<i64>>,
+    /// Number of entities in each occupied cell, since items can pile up.
+    enemies: HashMap<vec2<i64>, usize>,
+    items: HashMap<vec2<i64>, usize>,
+}
+
+impl Occupancy {
+    /// Indexes the map from scratch.
+    pub fn build(level_map: &LevelMap) -> Self {
+        let mut occupancy = Self::default();
+        for &cell in level_map.expansion_cells() {
+            occupancy.add_expansion(cell);
+        }
+        for enemy in level_map.enemies() {
+            occupancy.add_enemy(enemy.pos);
+        }
+        for item in level_map.items() {
+            occupancy.add_item(item.pos);
+        }
+        occupancy
+    }
+
+    pub fn is_expansion(&self, pos: vec2<i64>) -> bool {
+        self.expansion.contains(&pos)
+    }
+
+    pub fn has_enemy(&self, pos: vec2<i64>) -> bool {
+        self.enemies.contains_key(&pos)
+    }
+
+    pub fn has_item(&self, pos: vec2<i64>) -> bool {
+        self.items.contains_key(&pos)
+    }
+
+    pub fn add_expansion(&mut self, pos: vec2<i64>) {
+        self.expansion.insert(pos);
+    }
+
+    pub fn add_enemy(&mut self, pos: vec2<i64>) {
+        *self.enemies.entry(pos).or_default() += 1;
+    }
+
+    pub fn remove_enemy(&mut self, pos: vec2<i64>) {
+        decrement(&mut self.enemies, pos);
+    }
+
+    pub fn add_item(&mut self, pos: vec2<i64>) {
+        *self.items.entry(pos).or_default() += 1;
+    }
+
+    pub fn remove_item(&mut self, pos: vec2<i64>) {
+        decrement(&mut self.items, pos);
+    }
+}
+
+fn decrement(counts: &mut HashMap<vec2<i64>, usize>, pos: vec2<i64>) {
+    if let Some(count) = counts.get_mut(&pos) {
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(&pos);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use super::*;
+
+/// Counters collected over the course of a run.
+#[derive(Debug, Clone, Default)]
+pub struct RunStats {
+    pub enemies_defeated: u64,
+    pub potions_brewed: u64,
+    pub turns_taken: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Score {
+    pub enemies_defeated: u64,
+    pub days_survived: u64,
+    pub potions_brewed: u64,
+    pub coins: Coins,
+}
+
+impl Score {
+    pub fn total(&self) -> u64 {
+        self.enemies_defeated * 10
+            + self.days_survived * 25
+            + self.potions_brewed * 5
+            + self.coins.max(0) as u64
+    }
+}
+
+impl Model {
+    pub fn score(&self) -> Score {
+        Score {
+            enemies_defeated: self.stats.enemies_defeated,
+            days_survived: self.day.saturating_sub(self.start_day),
+            potions_brewed: self.stats.potions_brewed,
+            coins: self.player.backpack.coins,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_survived_count_from_the_start_day() {
+        let mut model = Model::new(0);
+        let preset = LevelPreset {
+            id: "late_start".to_string(),
+            seed: None,
+            start_day: 5,
+            coins: None,
+        };
+        preset.apply(&mut model);
+        assert_eq!(model.score().days_survived, 0);
+
+        model.day += 2;
+        assert_eq!(model.score().days_survived, 2);
+    }
+}
@@ -0,0 +1,156 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusKind {
+    Frozen,
+    Poisoned,
+    Burning,
+    Hasted,
+    Shielded,
+}
+
+impl StatusKind {
+    pub fn damage_per_turn(&self) -> DP {
+        match self {
+            StatusKind::Poisoned => 1,
+            StatusKind::Burning => 2,
+            StatusKind::Frozen | StatusKind::Hasted | StatusKind::Shielded => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Status {
+    pub kind: StatusKind,
+    pub turns: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Statuses {
+    pub active: Vec<Status>,
+}
+
+impl Statuses {
+    /// Applies a status, extending the duration if it is already active.
+    pub fn apply(&mut self, status: Status) {
+        match self
+            .active
+            .iter_mut()
+            .find(|active| active.kind == status.kind)
+        {
+            Some(active) => active.turns = active.turns.max(status.turns),
+            None => self.active.push(status),
+        }
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.active.iter().any(|status| status.kind == kind)
+    }
+
+    pub fn damage_per_turn(&self) -> DP {
+        self.active
+            .iter()
+            .map(|status| status.kind.damage_per_turn())
+            .sum()
+    }
+
+    /// Counts down one turn and removes expired statuses.
+    pub fn tick(&mut self) {
+        for status in &mut self.active {
+            status.turns = status.turns.saturating_sub(1);
+        }
+        self.active.retain(|status| status.turns > 0);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Terrain {
+    Ice,
+    Fire,
+    Swamp,
+}
+
+impl Terrain {
+    pub fn status(&self) -> Status {
+        match self {
+            Terrain::Ice => Status {
+                kind: StatusKind::Frozen,
+                turns: 1,
+            },
+            Terrain::Fire => Status {
+                kind: StatusKind::Burning,
+                turns: 2,
+            },
+            Terrain::Swamp => Status {
+                kind: StatusKind::Poisoned,
+                turns: 3,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Potion {
+    Haste,
+    Shield,
+}
+
+impl Potion {
+    pub fn status(&self) -> Status {
+        match self {
+            Potion::Haste => Status {
+                kind: StatusKind::Hasted,
+                turns: 3,
+            },
+            Potion::Shield => Status {
+                kind: StatusKind::Shielded,
+                turns: 3,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(kind: StatusKind, turns: usize) -> Status {
+        Status { kind, turns }
+    }
+
+    #[test]
+    fn statuses_expire_after_their_turns() {
+        let mut statuses = Statuses::default();
+        statuses.apply(status(StatusKind::Frozen, 1));
+        statuses.apply(status(StatusKind::Poisoned, 2));
+
+        statuses.tick();
+        assert!(!statuses.has(StatusKind::Frozen));
+        assert!(statuses.has(StatusKind::Poisoned));
+
+        statuses.tick();
+        assert!(statuses.active.is_empty());
+    }
+
+    #[test]
+    fn reapplying_keeps_the_longer_duration() {
+        let mut statuses = Statuses::default();
+        statuses.apply(status(StatusKind::Burning, 3));
+        statuses.apply(status(StatusKind::Burning, 1));
+        assert_eq!(statuses.active.len(), 1);
+        assert_eq!(statuses.active[0].turns, 3);
+
+        statuses.apply(status(StatusKind::Burning, 5));
+        assert_eq!(statuses.active[0].turns, 5);
+    }
+
+    #[test]
+    fn damage_adds_up_over_statuses() {
+        let mut statuses = Statuses::default();
+        assert_eq!(statuses.damage_per_turn(), 0);
+        statuses.apply(status(StatusKind::Poisoned, 2));
+        statuses.apply(status(StatusKind::Burning, 2));
+        statuses.apply(status(StatusKind::Frozen, 2));
+        assert_eq!(statuses.damage_per_turn(), 3);
+    }
+}
@@ -0,0 +1,80 @@
+use super::*;
+
+pub enum TimedEvent {
+    /// Returns enemies with finished actions to idle and removes dead enemies that faded out.
+    SettleEnemies,
+    NightStep,
+    SpawnEnemy(EnemySpec),
+    ExpirePopups,
+}
+
+pub struct ScheduledEvent {
+    pub at: Time,
+    pub event: TimedEvent,
+}
+
+pub struct Timeline {
+    pub time: Time,
+    pub events: Vec<ScheduledEvent>,
+}
+
+impl Timeline {
+    pub fn new() -> Self {
+        Self {
+            time: r32(0.0),
+            events: vec![],
+        }
+    }
+
+    pub fn schedule(&mut self, delay: Time, event: TimedEvent) {
+        self.schedule_at(self.time + delay, event);
+    }
+
+    /// Events scheduled for the same time fire in the order they were scheduled.
+    pub fn schedule_at(&mut self, at: Time, event: TimedEvent) {
+        let index = self.events.partition_point(|scheduled| scheduled.at <= at);
+        self.events.insert(index, ScheduledEvent { at, event });
+    }
+
+    /// Time of the earliest scheduled event, if any.
+    pub fn next_event_at(&self) -> Option<Time> {
+        self.events.first().map(|scheduled| scheduled.at)
+    }
+
+    pub fn pop_due(&mut self) -> Option<TimedEvent> {
+        let next = self.events.first()?;
+        if next.at > self.time {
+            return None;
+        }
+        Some(self.events.remove(0).event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_fire_in_time_order() {
+        let mut timeline = Timeline::new();
+        timeline.schedule(r32(1.0), TimedEvent::NightStep);
+        timeline.schedule(r32(0.5), TimedEvent::SettleEnemies);
+        timeline.schedule(r32(1.0), TimedEvent::ExpirePopups);
+        assert_eq!(timeline.next_event_at(), Some(r32(0.5)));
+        assert!(timeline.pop_due().is_none(), "nothing is due yet");
+
+        timeline.time = r32(0.5);
+        assert!(matches!(
+            timeline.pop_due(),
+            Some(TimedEvent::SettleEnemies)
+        ));
+        assert!(timeline.pop_due().is_none());
+
+        timeline.time = r32(2.0);
+        // Events due at the same time keep the order they were scheduled in.
+        assert!(matches!(timeline.pop_due(), Some(TimedEvent::NightStep)));
+        assert!(matches!(timeline.pop_due(), Some(TimedEvent::ExpirePopups)));
+        assert!(timeline.pop_due().is_none());
+        assert_eq!(timeline.next_event_at(), None);
+    }
+}
@@ -0,0 +1,146 @@
+use super::*;
+
+/// A broken game rule found by [Model::validate].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    EnemiesShareCell(vec2<i64>),
+    OutsideMap(Entity, vec2<i64>),
+    ItemUnderEnemy(vec2<i64>),
+    DuplicateExpansionCell(vec2<i64>),
+    NegativeCoins(Coins),
+    DeadEnemyNotRemoved {
+        id: EntityId,
+        health: HP,
+    },
+    /// The occupancy index no longer matches the entity positions.
+    StaleOccupancy,
+    /// The stored expansion frontier differs from one found from scratch.
+    StaleFrontier,
+    /// The id lookup index no longer matches the entity lists.
+    StaleSlots,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::EnemiesShareCell(pos) => write!(f, "several enemies at {pos:?}"),
+            Violation::OutsideMap(entity, pos) => {
+                write!(f, "{entity:?} outside the map at {pos:?}")
+            }
+            Violation::ItemUnderEnemy(pos) => write!(f, "item under an enemy at {pos:?}"),
+            Violation::DuplicateExpansionCell(pos) => {
+                write!(f, "expansion cell {pos:?} is listed more than once")
+            }
+            Violation::NegativeCoins(coins) => write!(f, "negative coins: {coins}"),
+            Violation::DeadEnemyNotRemoved { id, health } => {
+                write!(f, "enemy {id} has {health} health but was not removed")
+            }
+            Violation::StaleOccupancy => write!(f, "occupancy index is out of sync"),
+            Violation::StaleFrontier => write!(f, "expansion frontier is out of sync"),
+            Violation::StaleSlots => write!(f, "entity id index is out of sync"),
+        }
+    }
+}
+
+impl Model {
+    pub fn validate(&self) -> Vec<Violation> {
+        let mut violations = vec![];
+        let level_map = &self.level_map;
+
+        if !level_map.inside(self.player.pos) {
+            violations.push(Violation::OutsideMap(Entity::Player, self.player.pos));
+        }
+
+        let mut enemy_cells = HashSet::new();
+        for enemy in &level_map.enemies {
+            if !enemy_cells.insert(enemy.pos) {
+                violations.push(Violation::EnemiesShareCell(enemy.pos));
+            }
+            if !level_map.inside(enemy.pos) {
+                violations.push(Violation::OutsideMap(Entity::Enemy(enemy.id), enemy.pos));
+            }
+            if enemy.health <= 0 {
+                violations.push(Violation::DeadEnemyNotRemoved {
+                    id: enemy.id,
+                    health: enemy.health,
+                });
+            }
+        }
+
+        for item in &level_map.items {
+            if !level_map.inside(item.pos) {
+                violations.push(Violation::OutsideMap(Entity::Item(item.id), item.pos));
+            }
+            if enemy_cells.contains(&item.pos) {
+                violations.push(Violation::ItemUnderEnemy(item.pos));
+            }
+        }
+
+        let mut expansion_cells = HashSet::new();
+        for &cell in &level_map.expansion_cells {
+            if !expansion_cells.insert(cell) {
+                violations.push(Violation::DuplicateExpansionCell(cell));
+            }
+        }
+
+        if Occupancy::build(level_map) != level_map.occupancy {
+            violations.push(Violation::StaleOccupancy);
+        }
+        if level_map.sweep_frontier() != level_map.frontier {
+            violations.push(Violation::StaleFrontier);
+        }
+        if slots(&level_map.items, |item| item.id) != level_map.item_slots
+            || slots(&level_map.enemies, |enemy| enemy.id) != level_map.enemy_slots
+        {
+            violations.push(Violation::StaleSlots);
+        }
+
+        if self.player.backpack.coins < 0 {
+            violations.push(Violation::NegativeCoins(self.player.backpack.coins));
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_model_is_valid() {
+        assert_eq!(Model::new(0).validate(), []);
+    }
+
+    #[test]
+    fn finds_an_item_under_an_enemy() {
+        let mut model = Model::new(0);
+        let pos = model.level_map.enemies()[0].pos;
+        model.level_map.add_item(pos, ItemKind::Coins(1));
+        assert_eq!(model.validate(), [Violation::ItemUnderEnemy(pos)]);
+    }
+
+    #[test]
+    fn finds_a_stale_occupancy_index() {
+        let mut model = Model::new(0);
+        model.level_map.occupancy.add_enemy(vec2(2, 2));
+        assert_eq!(model.validate(), [Violation::StaleOccupancy]);
+    }
+
+    #[test]
+    fn finds_a_stale_frontier() {
+        let mut model = Model::new(0);
+        model.level_map.frontier.pop();
+        assert_eq!(model.validate(), [Violation::StaleFrontier]);
+    }
+
+    #[test]
+    fn finds_the_player_off_the_map() {
+        let mut model = Model::new(0);
+        model.player.pos = vec2(5, 5);
+        assert_eq!(
+            model.validate(),
+            [Violation::OutsideMap(Entity::Player, vec2(5, 5))]
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use crate::model::AnimationTime;
+
+use geng::prelude::*;
+use geng_utils::gif::GifFrame;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Playback {
+    #[default]
+    Loop,
+    Once,
+    PingPong,
+    HoldLast,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnimationDef {
+    pub file: std::path::PathBuf,
+    #[serde(default)]
+    pub playback: Playback,
+}
+
+pub struct Animation {
+    pub frames: Vec<GifFrame>,
+    pub playback: Playback,
+}
+
+impl Animation {
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.duration).sum()
+    }
+
+    pub fn frame(&self, time: AnimationTime) -> Option<&ugli::Texture> {
+        let duration = self.duration();
+        if duration <= 0.0 {
+            return self.frames.first().map(|frame| &frame.texture);
+        }
+
+        let t = match time {
+            AnimationTime::Clock(clock) => clock.as_f32(),
+            AnimationTime::Progress(progress) => progress.as_f32() * duration,
+        };
+        let t = match self.playback {
+            Playback::Loop => t.rem_euclid(duration),
+            Playback::Once => {
+                if t >= duration {
+                    return None;
+                }
+                t
+            }
+            Playback::PingPong => {
+                let t = t.rem_euclid(2.0 * duration);
+                if t < duration {
+                    t
+                } else {
+                    2.0 * duration - t
+                }
+            }
+            Playback::HoldLast => t.min(duration),
+        };
+
+        let mut start = 0.0;
+        for frame in &self.frames {
+            if t < start + frame.duration {
+                return Some(&frame.texture);
+            }
+            start += frame.duration;
+        }
+        self.frames.last().map(|frame| &frame.texture)
+    }
+}
+
+pub struct Animations {
+    pub animations: HashMap<String, Animation>,
+}
+
+impl Animations {
+    pub fn get(&self, key: &str) -> Option<&Animation> {
+        self.animations.get(key)
+    }
+}
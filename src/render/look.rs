@@ -0,0 +1,166 @@
+use super::Palette;
+use crate::model::*;
+
+use geng::prelude::*;
+
+/// Textures items can be drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemSprite {
+    Leaf,
+    Coin,
+    Sword,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemShape {
+    Sprite(ItemSprite),
+    Square,
+    Disc,
+}
+
+/// How an item is drawn, worked out apart from the drawing itself
+/// so that it can be checked without a window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ItemLook {
+    pub shape: ItemShape,
+    /// Tint of the sprite, or the colour of the shape.
+    pub color: Rgba<f32>,
+    /// Symbol drawn over the shape, so that items do not rely on colour alone.
+    pub glyph: Option<&'static str>,
+}
+
+impl ItemLook {
+    fn sprite(sprite: ItemSprite) -> Self {
+        Self {
+            shape: ItemShape::Sprite(sprite),
+            color: Rgba::WHITE,
+            glyph: None,
+        }
+    }
+
+    fn disc(color: Rgba<f32>, glyph: &'static str) -> Self {
+        Self {
+            shape: ItemShape::Disc,
+            color,
+            glyph: Some(glyph),
+        }
+    }
+}
+
+pub fn item_look(palette: &Palette, kind: &ItemKind) -> ItemLook {
+    match kind {
+        ItemKind::Sword { .. } => ItemLook::sprite(ItemSprite::Sword),
+        ItemKind::Ingredient(ingredient) => ingredient_look(palette, ingredient),
+        ItemKind::Potion(potion) => ItemLook::disc(palette.potion(*potion), potion_glyph(*potion)),
+        ItemKind::Gear(gear) => {
+            let color = palette.gear(gear.slot());
+            match gear.slot() {
+                GearSlot::Weapon => ItemLook {
+                    color,
+                    ..ItemLook::sprite(ItemSprite::Sword)
+                },
+                GearSlot::Armour => ItemLook {
+                    shape: ItemShape::Square,
+                    color,
+                    glyph: None,
+                },
+                GearSlot::Trinket => ItemLook {
+                    shape: ItemShape::Disc,
+                    color,
+                    glyph: None,
+                },
+            }
+        }
+        ItemKind::Coins(_) => ItemLook::sprite(ItemSprite::Coin),
+    }
+}
+
+pub fn ingredient_look(palette: &Palette, ingredient: &Ingredient) -> ItemLook {
+    let glyph = match ingredient {
+        Ingredient::Leaf => return ItemLook::sprite(ItemSprite::Leaf),
+        Ingredient::Ice => "❄",
+        Ingredient::Branch => "/",
+        Ingredient::Banana => ")",
+        Ingredient::Cherry => "♦",
+        Ingredient::Blueberry => "•",
+        Ingredient::Strawberry => "♥",
+    };
+    ItemLook::disc(palette.ingredient(*ingredient), glyph)
+}
+
+/// Potions share their glyph with the icon of the status they give.
+fn potion_glyph(potion: Potion) -> &'static str {
+    status_glyph(potion.status().kind)
+}
+
+pub fn status_glyph(kind: StatusKind) -> &'static str {
+    match kind {
+        StatusKind::Frozen => "❄",
+        StatusKind::Poisoned => "☠",
+        StatusKind::Burning => "♨",
+        StatusKind::Hasted => "»",
+        StatusKind::Shielded => "◈",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::PaletteKind;
+    use itertools::Itertools;
+
+    const INGREDIENTS: [Ingredient; 7] = [
+        Ingredient::Leaf,
+        Ingredient::Ice,
+        Ingredient::Branch,
+        Ingredient::Banana,
+        Ingredient::Cherry,
+        Ingredient::Blueberry,
+        Ingredient::Strawberry,
+    ];
+
+    fn palettes() -> impl Iterator<Item = Palette> {
+        PaletteKind::ALL.into_iter().flat_map(|kind| {
+            [false, true]
+                .into_iter()
+                .map(move |high_contrast| Palette::new(kind, high_contrast))
+        })
+    }
+
+    #[test]
+    fn generated_items_are_visible() {
+        let palettes: Vec<_> = palettes().collect();
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut generated = 0;
+        for day in 1..=20 {
+            for _ in 0..200 {
+                let Some(kind) = expansion_item(day, &mut rng) else {
+                    continue;
+                };
+                generated += 1;
+                for palette in &palettes {
+                    let look = item_look(palette, &kind);
+                    assert!(look.color.a > 0.0, "{look:?} is transparent");
+                    if let ItemKind::Ingredient(_) | ItemKind::Potion(_) = kind {
+                        assert!(
+                            matches!(look.shape, ItemShape::Sprite(_)) || look.glyph.is_some(),
+                            "{look:?} is told apart by colour alone"
+                        );
+                    }
+                }
+            }
+        }
+        assert!(generated > 0);
+    }
+
+    #[test]
+    fn ingredients_and_potions_have_distinct_glyphs() {
+        let palette = Palette::new(PaletteKind::Default, false);
+        let ingredients = INGREDIENTS
+            .iter()
+            .filter_map(|ingredient| ingredient_look(&palette, ingredient).glyph);
+        let potions = [Potion::Haste, Potion::Shield].map(potion_glyph);
+        let glyphs: Vec<_> = ingredients.chain(potions).collect();
+        assert_eq!(glyphs.iter().unique().count(), glyphs.len(), "{glyphs:?}");
+    }
+}
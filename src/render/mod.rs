@@ -1,35 +1,130 @@
-use crate::{assets::Assets, model::*};
+pub mod animation;
+mod look;
+mod palette;
+
+use self::{look::*, palette::Palette};
+
+use crate::{
+    assets::Assets,
+    console::Console,
+    high_scores::HighScores,
+    layout::{Anchor, Layout},
+    locale::Locale,
+    model::*,
+    settings::Settings,
+};
 
 use geng::{prelude::*, TextAlign};
 
+/// On-screen buttons for actions that otherwise need a keyboard.
+#[derive(Debug, Clone, Copy)]
+pub enum UiButton {
+    /// Uses the first item in the backpack, picking a target next if it needs one.
+    UseItem,
+    Cancel,
+    Restart,
+    HighScores,
+    Mute,
+    Pause,
+}
+
+/// Size of the HUD text in the top left corner, in reference pixels before text scaling.
+const HUD_FONT_SIZE: f32 = 24.0;
+const HUD_LINE_SPACING: f32 = 1.2;
+/// Lines of HUD text, counting the hint shown while choosing a target.
+const HUD_LINES: usize = 6;
+
+/// Seconds a sound caption stays on screen.
+const CAPTION_TIME: f32 = 1.5;
+
+/// Text shown in place of a sound effect.
+struct Caption {
+    text: String,
+    time_left: f32,
+}
+
 #[allow(dead_code)]
 pub struct GameRender {
     geng: Geng,
     assets: Rc<Assets>,
+    locale: Rc<Locale>,
+    palette: Palette,
+    /// Multiplier for the size of text drawn over the map and in the HUD.
+    text_scale: f32,
+    /// Draws moving entities at their destination instead of tweening them.
+    reduced_motion: bool,
+    show_captions: bool,
+    captions: Vec<Caption>,
 }
 
 impl GameRender {
-    pub fn new(geng: &Geng, assets: &Rc<Assets>) -> Self {
+    pub fn new(geng: &Geng, assets: &Rc<Assets>, locale: &Rc<Locale>, settings: &Settings) -> Self {
         Self {
             geng: geng.clone(),
             assets: assets.clone(),
+            locale: locale.clone(),
+            palette: Palette::new(settings.palette, settings.high_contrast),
+            text_scale: settings.text_scale,
+            reduced_motion: settings.reduced_motion,
+            show_captions: settings.captions,
+            captions: Vec::new(),
         }
     }
 
-    pub fn draw(&mut self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+    pub fn update(&mut self, delta_time: f32) {
+        for caption in &mut self.captions {
+            caption.time_left -= delta_time;
+        }
+        self.captions.retain(|caption| caption.time_left > 0.0);
+    }
+
+    /// Shows a caption for a sound that has just been played.
+    /// Enemies making the sound are named in the caption.
+    pub fn caption(&mut self, model: &Model, sound: &SoundKind, source: Option<Entity>) {
+        if !self.show_captions {
+            return;
+        }
+        let sound = self.locale.get(sound_caption_key(sound));
+        let source = match source {
+            Some(Entity::Enemy(id)) => model.level_map.enemy(id),
+            _ => None,
+        };
+        let text = match source {
+            Some(enemy) => self.locale.format(
+                "caption.from",
+                &[
+                    ("sound", &sound),
+                    ("source", &self.locale.get(enemy_key(enemy.kind))),
+                ],
+            ),
+            None => sound.to_string(),
+        };
+        self.captions.retain(|caption| caption.text != text);
+        self.captions.push(Caption {
+            text,
+            time_left: CAPTION_TIME,
+        });
+    }
+
+    pub fn set_assets(&mut self, assets: &Rc<Assets>) {
+        self.assets = assets.clone();
+    }
+
+    pub fn draw(
+        &mut self,
+        model: &Model,
+        hovered_cell: Option<vec2<i64>>,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
         for pos in model.level_map.cells_iter() {
             let x = pos.x as f32;
             let y = pos.y as f32;
+            let color = self.palette.terrain(model.level_map.terrain.get(&pos));
             self.geng.draw2d().quad(
                 framebuffer,
                 &model.camera,
                 Aabb2::point(vec2(x, y)).extend_symmetric(vec2(0.45, 0.45)),
-                Rgba {
-                    r: 0.2,
-                    g: 0.2,
-                    b: 0.2,
-                    a: 1.0,
-                },
+                color,
             );
         }
 
@@ -41,117 +136,947 @@ impl GameRender {
                     framebuffer,
                     &model.camera,
                     Aabb2::point(vec2(x, y)).extend_symmetric(vec2(0.45, 0.45)),
-                    Rgba {
-                        r: 0.3,
-                        g: 0.3,
-                        b: 0.1,
-                        a: 0.5,
-                    },
+                    self.palette.expansion,
                 );
             }
         }
 
+        if let (Some(slot), Some(cell)) = (model.targeting, hovered_cell) {
+            self.draw_targeting(model, slot, cell, framebuffer);
+        }
+
         let player_pos = model.player.pos.map(|x| x as f32);
-        self.geng.draw2d().textured_quad(
+        let (key, time) = model.player.animation(model.now());
+        self.draw_animation(
             framebuffer,
             &model.camera,
+            key,
+            time,
             Aabb2::point(player_pos).extend_symmetric(vec2(0.4, 0.4)),
-            &self.assets.sprites.player,
             Rgba::WHITE,
         );
+        self.draw_statuses(
+            framebuffer,
+            &model.camera,
+            &model.player.statuses,
+            player_pos,
+        );
 
-        for item in &model.level_map.items {
+        for item in model.level_map.items() {
             let item_pos = item.pos.map(|x| x as f32);
-            match &item.kind {
-                ItemKind::Ingredient(ingredient) => match ingredient {
-                    Ingredient::Leaf => self.geng.draw2d().textured_quad(
-                        framebuffer,
-                        &model.camera,
-                        Aabb2::point(item_pos).extend_symmetric(vec2(0.3, 0.3)),
-                        &self.assets.sprites.leaf,
-                        Rgba::WHITE,
-                    ),
-                    _ => {}
-                },
-                ItemKind::Sword { damage } => {
-                    self.geng.draw2d().textured_quad(
-                        framebuffer,
-                        &model.camera,
-                        Aabb2::point(item_pos).extend_symmetric(vec2(0.3, 0.3)),
-                        &self.assets.sprites.sword,
-                        Rgba::WHITE,
-                    );
-                    self.geng.default_font().draw(
-                        framebuffer,
-                        &model.camera,
-                        &format!("{}", damage),
-                        vec2(TextAlign::RIGHT, TextAlign::BOTTOM),
-                        mat3::translate(item_pos + vec2(0.4, -0.4)) * mat3::scale_uniform(0.4),
-                        Rgba::BLACK,
-                    )
-                }
-            }
+            let size = match item.kind {
+                ItemKind::Coins(_) => 0.25,
+                _ => 0.3,
+            };
+            self.draw_look(
+                framebuffer,
+                &model.camera,
+                item_look(&self.palette, &item.kind),
+                Aabb2::point(item_pos).extend_symmetric(vec2(size, size)),
+            );
+            let (label, color) = match item.kind {
+                ItemKind::Coins(coins) => (coins, self.palette.coins),
+                ItemKind::Sword { damage } => (damage, Rgba::BLACK),
+                _ => continue,
+            };
+            self.assets.fonts.main.draw(
+                framebuffer,
+                &model.camera,
+                &format!("{}", label),
+                vec2(TextAlign::RIGHT, TextAlign::BOTTOM),
+                mat3::translate(item_pos + vec2(0.4, -0.4))
+                    * mat3::scale_uniform(self.text_size(0.4)),
+                color,
+            );
         }
 
         for enemy in model
             .level_map
-            .enemies
+            .enemies()
             .iter()
             .chain(model.level_map.dead_enemies.iter())
         {
-            let enemy_pos = enemy.pos.map(|x| x as f32);
-
-            let animation = match &enemy.state {
-                EnemyState::Idle => match enemy.mode {
-                    EnemyMode::Normal => &self.assets.sprites.level_1_bot_idle_normal,
-                    EnemyMode::Damaged => &self.assets.sprites.level_1_bot_idle_damaged,
-                },
-                EnemyState::Action(Cooldown { action, .. }) => match action {
-                    EnemyAction::TakeDamage => &self.assets.sprites.level_1_bot_idle_damaged,
-                    EnemyAction::Attack => &self.assets.sprites.level_1_bot_idle_normal,
-                    EnemyAction::Die => &self.assets.sprites.level_1_bot_die,
-                    EnemyAction::Spawn => &self.assets.sprites.level_1_bot_idle_normal,
-                },
-            };
-
-            let total_duration = animation.iter().map(|frame| frame.duration).sum();
-            let clock = match enemy.state {
-                EnemyState::Action(Cooldown {
-                    leftover, total, ..
-                }) => r32(total_duration) * (r32(1.0) - leftover / total),
-                EnemyState::Idle => enemy.animation_clock,
+            let enemy_pos = if self.reduced_motion {
+                enemy.pos.map(|x| x as f32)
+            } else {
+                enemy.render_pos(model.now())
             };
 
-            let animation_clock_rem =
-                clock - (clock / r32(total_duration)).floor() * r32(total_duration);
-
-            let mut t = r32(0.0);
-            let mut target_frame = &animation[0];
-            for frame in animation {
-                if animation_clock_rem >= t && animation_clock_rem < t + r32(frame.duration) {
-                    target_frame = frame;
-                    break;
-                } else {
-                    t += r32(frame.duration);
-                }
-            }
-            self.geng.draw2d().textured_quad(
+            let (key, time) = enemy.animation(model.now());
+            self.draw_animation(
                 framebuffer,
                 &model.camera,
+                key,
+                time,
                 Aabb2::point(enemy_pos).extend_symmetric(vec2(0.45, 0.45)),
-                &target_frame.texture,
-                Rgba::WHITE,
+                self.palette.enemy_tint(enemy.kind),
             );
             if enemy.health > 0 {
-                self.geng.default_font().draw(
+                self.assets.fonts.main.draw(
                     framebuffer,
                     &model.camera,
                     &format!("{}", enemy.health),
                     vec2(TextAlign::RIGHT, TextAlign::BOTTOM),
-                    mat3::translate(enemy_pos + vec2(0.4, -0.4)) * mat3::scale_uniform(0.4),
-                    Rgba::RED,
+                    mat3::translate(enemy_pos + vec2(0.4, -0.4))
+                        * mat3::scale_uniform(self.text_size(0.4)),
+                    self.palette.enemy_health,
                 )
             }
+            self.draw_statuses(framebuffer, &model.camera, &enemy.statuses, enemy_pos);
+        }
+
+        for popup in &model.popups {
+            let progress = popup.lifetime.progress(model.now()).as_f32();
+            let text = match popup.kind {
+                PopupKind::Coins(coins) => format!("+{coins}"),
+            };
+            let rise = if self.reduced_motion {
+                0.0
+            } else {
+                0.5 * progress
+            };
+            self.assets.fonts.main.draw(
+                framebuffer,
+                &model.camera,
+                &text,
+                vec2(TextAlign::CENTER, TextAlign::BOTTOM),
+                mat3::translate(popup.pos + vec2(0.0, 0.3 + rise))
+                    * mat3::scale_uniform(self.text_size(0.35)),
+                Rgba {
+                    a: 1.0 - progress,
+                    ..self.palette.coins
+                },
+            );
+        }
+
+        if let State::Night(night) = &model.state {
+            // Reduced motion skips the fade and keeps the night at full darkness.
+            let darkness = if self.reduced_motion {
+                1.0
+            } else {
+                night.darkness(model.now())
+            };
+            self.geng.draw2d().quad(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                Layout::new(framebuffer.size()).screen(),
+                Rgba {
+                    a: self.palette.night.a * darkness,
+                    ..self.palette.night
+                },
+            );
+        }
+
+        if let State::GameOver = model.state {
+            self.draw_game_over(model, framebuffer);
+        }
+
+        if let Some(cell) = hovered_cell {
+            self.draw_tooltip(model, cell, framebuffer);
+        }
+        self.draw_hud(model, framebuffer);
+        self.draw_captions(framebuffer);
+    }
+
+    /// Height of the HUD text block, in reference pixels.
+    fn hud_height(&self) -> f32 {
+        HUD_LINES as f32 * self.text_size(HUD_FONT_SIZE) * HUD_LINE_SPACING
+    }
+
+    fn text_size(&self, size: f32) -> f32 {
+        size * self.text_scale
+    }
+
+    fn draw_captions(&self, framebuffer: &mut ugli::Framebuffer) {
+        let layout = Layout::new(framebuffer.size());
+        let font_size = self.text_size(20.0);
+        let bottom = backpack_top(&layout) + 16.0;
+        for (i, caption) in self.captions.iter().rev().enumerate() {
+            let alpha = (caption.time_left / 0.3).min(1.0);
+            self.assets.fonts.main.draw(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                &format!("[{}]", caption.text),
+                vec2(TextAlign::CENTER, TextAlign::BOTTOM),
+                mat3::translate(layout.point(
+                    Anchor::Bottom,
+                    vec2(0.0, bottom + font_size * 1.2 * i as f32),
+                )) * mat3::scale_uniform(layout.px(font_size)),
+                Rgba {
+                    a: alpha,
+                    ..self.palette.text
+                },
+            );
+        }
+    }
+
+    fn draw_animation(
+        &self,
+        framebuffer: &mut ugli::Framebuffer,
+        camera: &impl geng::AbstractCamera2d,
+        key: &str,
+        time: AnimationTime,
+        aabb: Aabb2<f32>,
+        color: Rgba<f32>,
+    ) {
+        let Some(animation) = self.assets.sprites.animations.get(key) else {
+            return;
+        };
+        if let Some(texture) = animation.frame(time) {
+            self.geng
+                .draw2d()
+                .textured_quad(framebuffer, camera, aabb, texture, color);
         }
     }
+
+    fn draw_statuses(
+        &self,
+        framebuffer: &mut ugli::Framebuffer,
+        camera: &Camera2d,
+        statuses: &Statuses,
+        entity_pos: vec2<f32>,
+    ) {
+        for (i, status) in statuses.active.iter().enumerate() {
+            let icon = status_glyph(status.kind);
+            let color = self.palette.status(status.kind);
+            let pos = entity_pos + vec2(-0.35 + 0.2 * i as f32, 0.35);
+            self.geng
+                .draw2d()
+                .circle(framebuffer, camera, pos, 0.1, Rgba::new(0.0, 0.0, 0.0, 0.7));
+            self.assets.fonts.main.draw(
+                framebuffer,
+                camera,
+                icon,
+                vec2(TextAlign::CENTER, TextAlign::CENTER),
+                mat3::translate(pos) * mat3::scale_uniform(0.15),
+                color,
+            );
+        }
+    }
+
+    fn draw_game_over(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let layout = Layout::new(framebuffer.size());
+        self.geng.draw2d().quad(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            layout.screen(),
+            Rgba::new(0.0, 0.0, 0.0, 0.6),
+        );
+        self.assets.fonts.main.draw(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            self.locale.get("game_over.title"),
+            vec2(TextAlign::CENTER, TextAlign::CENTER),
+            mat3::translate(layout.point(Anchor::Center, vec2(0.0, 96.0)))
+                * mat3::scale_uniform(layout.px(64.0)),
+            Rgba::WHITE,
+        );
+
+        let score = model.score();
+        let lines = [
+            self.locale
+                .format("score.total", &[("score", &score.total())]),
+            self.locale
+                .format("score.days", &[("days", &score.days_survived)]),
+            self.locale
+                .format("score.enemies", &[("enemies", &score.enemies_defeated)]),
+            self.locale
+                .format("score.potions", &[("potions", &score.potions_brewed)]),
+            self.locale
+                .format("score.coins", &[("coins", &score.coins)]),
+            String::new(),
+            self.locale.get("game_over.restart").to_string(),
+            self.locale.get("high_scores.hint").to_string(),
+        ];
+        for (i, line) in lines.iter().enumerate() {
+            self.assets.fonts.main.draw(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                line,
+                vec2(TextAlign::CENTER, TextAlign::CENTER),
+                mat3::translate(layout.point(Anchor::Center, vec2(0.0, 32.0 - 28.0 * i as f32)))
+                    * mat3::scale_uniform(layout.px(22.0)),
+                Rgba::WHITE,
+            );
+        }
+    }
+
+    /// Prints model internals: cell coordinates, enemy states, the current state
+    /// and the cells the map can be expanded to.
+    pub fn draw_debug(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let now = model.now();
+        let font = &self.assets.fonts.main;
+
+        let adjacent: Vec<vec2<i64>> = model.level_map.adjacent_cells_iter().collect();
+        for &pos in &adjacent {
+            self.geng.draw2d().quad(
+                framebuffer,
+                &model.camera,
+                Aabb2::point(pos.map(|x| x as f32)).extend_symmetric(vec2(0.45, 0.45)),
+                self.palette.debug_frontier,
+            );
+        }
+        for pos in model.level_map.cells_iter().chain(adjacent.iter().copied()) {
+            font.draw(
+                framebuffer,
+                &model.camera,
+                &format!("{},{}", pos.x, pos.y),
+                vec2(TextAlign::LEFT, TextAlign::TOP),
+                mat3::translate(pos.map(|x| x as f32) + vec2(-0.45, 0.45))
+                    * mat3::scale_uniform(0.15),
+                Rgba {
+                    a: 0.8,
+                    ..self.palette.debug_text
+                },
+            );
+        }
+
+        let state = match &model.state {
+            State::Night(night) => format!(
+                "Night: {:?}, {:.2}s left, pending enemies {:?}",
+                night.step.action,
+                night.step.leftover(now).as_f32(),
+                night.pending_enemies,
+            ),
+            state => format!("{state:?}"),
+        };
+        let mut lines = vec![
+            format!("time {:.2}, day {}", now.as_f32(), model.day),
+            format!("state: {state}"),
+            format!("targeting: {:?}", model.targeting),
+        ];
+        let enemies = model
+            .level_map
+            .enemies()
+            .iter()
+            .map(|enemy| ("enemy", enemy))
+            .chain(
+                model
+                    .level_map
+                    .dead_enemies
+                    .iter()
+                    .map(|enemy| ("fading", enemy)),
+            );
+        for (label, enemy) in enemies {
+            let state = match &enemy.state {
+                EnemyState::Idle => "Idle".to_string(),
+                EnemyState::Action(cooldown) => format!(
+                    "{:?}, {:.2}s left",
+                    cooldown.action,
+                    cooldown.leftover(now).as_f32()
+                ),
+            };
+            let (animation, time) = enemy.animation(now);
+            lines.push(format!(
+                "{label} {} {:?} at {},{}: {state}, {:?}, {animation} {time:?}",
+                enemy.id, enemy.kind, enemy.pos.x, enemy.pos.y, enemy.mode,
+            ));
+        }
+        lines.push(format!(
+            "adjacent cells: {}",
+            adjacent
+                .iter()
+                .map(|pos| format!("{},{}", pos.x, pos.y))
+                .collect::<Vec<_>>()
+                .join(" ")
+        ));
+
+        for (i, line) in lines.iter().enumerate() {
+            font.draw(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                line,
+                vec2(TextAlign::LEFT, TextAlign::BOTTOM),
+                mat3::translate(vec2(8.0, 8.0 + 18.0 * (lines.len() - 1 - i) as f32))
+                    * mat3::scale_uniform(14.0),
+                self.palette.debug_text,
+            );
+        }
+    }
+
+    pub fn draw_console(&self, console: &Console, framebuffer: &mut ugli::Framebuffer) {
+        let layout = Layout::new(framebuffer.size());
+        let screen = layout.screen();
+        let line_height = layout.px(22.0);
+        let lines = console.output.len() + 1;
+        let height = line_height * lines as f32 + layout.px(16.0);
+        self.geng.draw2d().quad(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            Aabb2::point(vec2(screen.min.x, screen.max.y - height))
+                .extend_positive(vec2(screen.width(), height)),
+            Rgba::new(0.0, 0.0, 0.0, 0.8),
+        );
+        let input = format!("> {}_", console.input);
+        for (i, line) in console.output.iter().chain([&input]).enumerate() {
+            self.assets.fonts.main.draw(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                line,
+                vec2(TextAlign::LEFT, TextAlign::TOP),
+                mat3::translate(vec2(
+                    screen.min.x + layout.px(8.0),
+                    screen.max.y - layout.px(8.0) - line_height * i as f32,
+                )) * mat3::scale_uniform(layout.px(18.0)),
+                Rgba::new(0.7, 1.0, 0.7, 1.0),
+            );
+        }
+    }
+
+    /// Draws the local high-score table on top of everything, highlighting the given place.
+    pub fn draw_high_scores(
+        &self,
+        high_scores: &HighScores,
+        highlight: Option<usize>,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let layout = Layout::new(framebuffer.size());
+        self.geng.draw2d().quad(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            layout.screen(),
+            Rgba::new(0.0, 0.0, 0.0, 0.85),
+        );
+        // Entries are long, so they shrink to fit narrow screens.
+        let entry_size = 22.0_f32.min(layout.available_width() / 32.0);
+        self.assets.fonts.main.draw(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            self.locale.get("high_scores.title"),
+            vec2(TextAlign::CENTER, TextAlign::CENTER),
+            mat3::translate(layout.point(Anchor::Top, vec2(0.0, -64.0)))
+                * mat3::scale_uniform(layout.px(48.0)),
+            Rgba::WHITE,
+        );
+
+        let lines: Vec<String> = if high_scores.entries.is_empty() {
+            vec![self.locale.get("high_scores.empty").to_string()]
+        } else {
+            high_scores
+                .entries
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| {
+                    self.locale.format(
+                        "high_scores.entry",
+                        &[
+                            ("rank", &(i + 1)),
+                            ("score", &entry.score),
+                            ("days", &entry.days_survived),
+                            ("seed", &entry.seed),
+                            ("date", &entry.date),
+                        ],
+                    )
+                })
+                .collect()
+        };
+        for (i, line) in lines.iter().enumerate() {
+            let color = if highlight == Some(i) {
+                Rgba::new(1.0, 0.85, 0.2, 1.0)
+            } else {
+                Rgba::WHITE
+            };
+            self.assets.fonts.main.draw(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                line,
+                vec2(TextAlign::CENTER, TextAlign::CENTER),
+                mat3::translate(layout.point(Anchor::Top, vec2(0.0, -134.0 - 32.0 * i as f32)))
+                    * mat3::scale_uniform(layout.px(entry_size)),
+                color,
+            );
+        }
+        self.assets.fonts.main.draw(
+            framebuffer,
+            &geng::PixelPerfectCamera,
+            self.locale.get("high_scores.close"),
+            vec2(TextAlign::CENTER, TextAlign::CENTER),
+            mat3::translate(layout.point(Anchor::Bottom, vec2(0.0, 32.0)))
+                * mat3::scale_uniform(layout.px(20.0)),
+            Rgba::WHITE,
+        );
+    }
+
+    fn draw_hud(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let state_key = match model.state {
+            State::Day => "state.day",
+            State::ExpandMap => "state.expand_map",
+            State::Shop => "state.shop",
+            State::Night(_) => "state.night",
+            State::GameOver => "state.game_over",
+        };
+        let lines = [
+            format!(
+                "{} — {}",
+                self.locale.format("hud.day", &[("day", &model.day)]),
+                self.locale.get(state_key)
+            ),
+            self.locale
+                .format("hud.turns", &[("turns", &model.player.turns_remaining)]),
+            self.locale
+                .format("hud.health", &[("health", &model.player.health)]),
+            self.locale
+                .format("hud.coins", &[("coins", &model.player.backpack.coins)]),
+            self.locale.format(
+                "hud.ingredients",
+                &[("count", &model.player.backpack.ingredients.len())],
+            ),
+        ];
+
+        let layout = Layout::new(framebuffer.size());
+        let font_size = layout.px(self.text_size(HUD_FONT_SIZE));
+        let mut pos = layout.point(Anchor::TopLeft, vec2(0.0, 0.0));
+        for line in lines {
+            self.assets.fonts.main.draw(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                &line,
+                vec2(TextAlign::LEFT, TextAlign::TOP),
+                mat3::translate(pos) * mat3::scale_uniform(font_size),
+                self.palette.text,
+            );
+            pos.y -= font_size * HUD_LINE_SPACING;
+        }
+        if model.targeting.is_some() {
+            self.assets.fonts.main.draw(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                self.locale.get("hud.choose_target"),
+                vec2(TextAlign::LEFT, TextAlign::TOP),
+                mat3::translate(pos) * mat3::scale_uniform(font_size),
+                self.palette.text,
+            );
+        }
+
+        self.draw_backpack(model, framebuffer);
+        self.draw_equipment(model, framebuffer);
+    }
+
+    fn draw_equipment(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let layout = Layout::new(framebuffer.size());
+        let camera = &geng::PixelPerfectCamera;
+        let font = &self.assets.fonts.main;
+
+        let title_pos = equipment_slot_aabb(&layout, self.hud_height(), 0).top_left()
+            + vec2(0.0, layout.px(8.0));
+        font.draw(
+            framebuffer,
+            camera,
+            &self.locale.format(
+                "hud.equipment",
+                &[
+                    ("attack", &model.player.equipment.attack()),
+                    ("defense", &model.player.equipment.defense()),
+                ],
+            ),
+            vec2(TextAlign::LEFT, TextAlign::BOTTOM),
+            mat3::translate(title_pos) * mat3::scale_uniform(layout.px(20.0)),
+            Rgba::WHITE,
+        );
+
+        let equipped = GEAR_SLOTS
+            .iter()
+            .map(|&slot| (gear_slot_key(slot), model.player.equipment.get(slot)));
+        let carried = model
+            .player
+            .backpack
+            .gear
+            .iter()
+            .map(|&gear| ("hud.carried", Some(gear)));
+        for (i, (label_key, gear)) in equipped.chain(carried).enumerate() {
+            let aabb = equipment_slot_aabb(&layout, self.hud_height(), i);
+            let background = if i < GEAR_SLOTS.len() {
+                self.palette.equipment_panel
+            } else {
+                self.palette.panel
+            };
+            self.geng
+                .draw2d()
+                .quad(framebuffer, camera, aabb, background);
+            let label = match gear {
+                Some(gear) => format!(
+                    "{}: {}",
+                    self.locale.get(label_key),
+                    self.locale.get(gear_key(gear))
+                ),
+                None => format!("{}: —", self.locale.get(label_key)),
+            };
+            font.draw(
+                framebuffer,
+                camera,
+                &label,
+                vec2(TextAlign::LEFT, TextAlign::CENTER),
+                mat3::translate(vec2(aabb.min.x + layout.px(8.0), aabb.center().y))
+                    * mat3::scale_uniform(layout.px(16.0)),
+                Rgba::WHITE,
+            );
+        }
+    }
+
+    /// Returns the action for a HUD element under the given screen position, if any.
+    pub fn hud_action_at(
+        &self,
+        model: &Model,
+        framebuffer_size: vec2<usize>,
+        screen_pos: vec2<f32>,
+    ) -> Option<Action> {
+        let layout = Layout::new(framebuffer_size);
+        let count = model.player.backpack.ingredients.len();
+        if let Some(slot) =
+            (0..count).find(|&slot| backpack_slot_aabb(&layout, count, slot).contains(screen_pos))
+        {
+            return Some(Action::UseItem(slot));
+        }
+        if let Some(&slot) = GEAR_SLOTS.iter().enumerate().find_map(|(i, slot)| {
+            equipment_slot_aabb(&layout, self.hud_height(), i)
+                .contains(screen_pos)
+                .then_some(slot)
+        }) {
+            return Some(Action::Unequip(slot));
+        }
+        (0..model.player.backpack.gear.len())
+            .find(|&index| {
+                equipment_slot_aabb(&layout, self.hud_height(), GEAR_SLOTS.len() + index)
+                    .contains(screen_pos)
+            })
+            .map(Action::Equip)
+    }
+
+    pub fn touch_buttons(
+        &self,
+        model: &Model,
+        framebuffer_size: vec2<usize>,
+    ) -> Vec<(UiButton, Aabb2<f32>)> {
+        let layout = Layout::new(framebuffer_size);
+        let mut buttons = vec![UiButton::Pause, UiButton::HighScores, UiButton::Mute];
+        if model.targeting.is_some() {
+            buttons.push(UiButton::Cancel);
+        } else if let (State::Day, false) =
+            (&model.state, model.player.backpack.ingredients.is_empty())
+        {
+            buttons.push(UiButton::UseItem);
+        }
+        if let State::GameOver = model.state {
+            buttons.push(UiButton::Restart);
+        }
+        buttons
+            .into_iter()
+            .enumerate()
+            .map(|(i, button)| (button, touch_button_aabb(&layout, i)))
+            .collect()
+    }
+
+    pub fn draw_touch_buttons(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let layout = Layout::new(framebuffer.size());
+        for (button, aabb) in self.touch_buttons(model, framebuffer.size()) {
+            self.geng.draw2d().quad(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                aabb,
+                self.palette.panel,
+            );
+            let label = match button {
+                UiButton::UseItem => {
+                    let ingredient = &model.player.backpack.ingredients[0];
+                    self.locale.format(
+                        "touch.use_item",
+                        &[("name", &self.locale.get(ingredient_key(ingredient)))],
+                    )
+                }
+                button => self.locale.get(touch_button_key(button)).to_owned(),
+            };
+            self.assets.fonts.main.draw(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                &label,
+                vec2(TextAlign::CENTER, TextAlign::CENTER),
+                mat3::translate(aabb.center()) * mat3::scale_uniform(layout.px(20.0)),
+                Rgba::WHITE,
+            );
+        }
+    }
+
+    fn draw_backpack(&self, model: &Model, framebuffer: &mut ugli::Framebuffer) {
+        let layout = Layout::new(framebuffer.size());
+        let ingredients = &model.player.backpack.ingredients;
+        for (slot, ingredient) in ingredients.iter().enumerate() {
+            let aabb = backpack_slot_aabb(&layout, ingredients.len(), slot);
+            let background = if model.targeting == Some(slot) {
+                self.palette.panel_selected
+            } else {
+                self.palette.panel
+            };
+            self.geng
+                .draw2d()
+                .quad(framebuffer, &geng::PixelPerfectCamera, aabb, background);
+            self.draw_look(
+                framebuffer,
+                &geng::PixelPerfectCamera,
+                ingredient_look(&self.palette, ingredient),
+                aabb.extend_uniform(-aabb.width() * 0.15),
+            );
+            if slot < 9 {
+                self.assets.fonts.main.draw(
+                    framebuffer,
+                    &geng::PixelPerfectCamera,
+                    &format!("{}", slot + 1),
+                    vec2(TextAlign::LEFT, TextAlign::TOP),
+                    mat3::translate(aabb.top_left() + vec2(4.0, -4.0))
+                        * mat3::scale_uniform(self.text_size(aabb.height() * 0.3)),
+                    self.palette.text,
+                );
+            }
+        }
+    }
+
+    fn draw_look(
+        &self,
+        framebuffer: &mut ugli::Framebuffer,
+        camera: &impl geng::AbstractCamera2d,
+        look: ItemLook,
+        aabb: Aabb2<f32>,
+    ) {
+        match look.shape {
+            ItemShape::Sprite(sprite) => {
+                let texture = match sprite {
+                    ItemSprite::Leaf => &self.assets.sprites.leaf,
+                    ItemSprite::Coin => &self.assets.sprites.coin,
+                    ItemSprite::Sword => &self.assets.sprites.sword,
+                };
+                self.geng
+                    .draw2d()
+                    .textured_quad(framebuffer, camera, aabb, texture, look.color);
+            }
+            ItemShape::Square => {
+                self.geng
+                    .draw2d()
+                    .quad(framebuffer, camera, aabb, look.color);
+            }
+            ItemShape::Disc => self.geng.draw2d().circle(
+                framebuffer,
+                camera,
+                aabb.center(),
+                aabb.width().min(aabb.height()) / 2.0,
+                look.color,
+            ),
+        }
+        if let Some(glyph) = look.glyph {
+            self.assets.fonts.main.draw(
+                framebuffer,
+                camera,
+                glyph,
+                vec2(TextAlign::CENTER, TextAlign::CENTER),
+                mat3::translate(aabb.center()) * mat3::scale_uniform(aabb.height() * 0.7),
+                Rgba::BLACK,
+            );
+        }
+    }
+
+    fn draw_targeting(
+        &self,
+        model: &Model,
+        slot: usize,
+        target: vec2<i64>,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let Some(ingredient) = model.player.backpack.ingredients.get(slot) else {
+            return;
+        };
+        let Brew::Area { radius, .. } = ingredient.brew() else {
+            return;
+        };
+        for cell in model.level_map.cells_iter() {
+            if manhattan_dist(cell, target) > radius {
+                continue;
+            }
+            self.geng.draw2d().quad(
+                framebuffer,
+                &model.camera,
+                Aabb2::point(cell.map(|x| x as f32)).extend_symmetric(vec2(0.45, 0.45)),
+                self.palette.targeting,
+            );
+        }
+    }
+
+    fn draw_tooltip(&self, model: &Model, cell: vec2<i64>, framebuffer: &mut ugli::Framebuffer) {
+        let Some(text) = self.tooltip_text(model, cell) else {
+            return;
+        };
+        let pos = cell.map(|x| x as f32) + vec2(0.0, 0.55);
+        self.assets.fonts.main.draw(
+            framebuffer,
+            &model.camera,
+            &text,
+            vec2(TextAlign::CENTER, TextAlign::BOTTOM),
+            mat3::translate(pos) * mat3::scale_uniform(self.text_size(0.3)),
+            self.palette.text,
+        );
+    }
+
+    fn tooltip_text(&self, model: &Model, cell: vec2<i64>) -> Option<String> {
+        let item = match model.entity_at(cell)? {
+            Entity::Player => return Some(self.locale.get("tooltip.player").to_owned()),
+            Entity::Enemy(id) => {
+                let enemy = model.level_map.enemy(id)?;
+                return Some(self.locale.format(
+                    "tooltip.enemy",
+                    &[
+                        ("name", &self.locale.get(enemy_key(enemy.kind))),
+                        ("health", &enemy.health),
+                    ],
+                ));
+            }
+            Entity::Item(id) => model.level_map.item(id)?,
+        };
+        Some(match &item.kind {
+            ItemKind::Sword { damage } => {
+                self.locale.format("tooltip.sword", &[("damage", damage)])
+            }
+            ItemKind::Ingredient(ingredient) => {
+                self.locale.get(ingredient_key(ingredient)).to_owned()
+            }
+            ItemKind::Potion(potion) => self.locale.get(potion_key(potion)).to_owned(),
+            ItemKind::Coins(coins) => self.locale.format("tooltip.coins", &[("coins", coins)]),
+            ItemKind::Gear(gear) => self.locale.format(
+                "tooltip.gear",
+                &[
+                    ("name", &self.locale.get(gear_key(*gear))),
+                    ("attack", &gear.attack()),
+                    ("defense", &gear.defense()),
+                ],
+            ),
+        })
+    }
+}
+
+const GEAR_SLOTS: [GearSlot; 3] = [GearSlot::Weapon, GearSlot::Armour, GearSlot::Trinket];
+
+/// Equipped slots come first, followed by the gear carried in the backpack.
+fn equipment_slot_aabb(layout: &Layout, hud_height: f32, index: usize) -> Aabb2<f32> {
+    let size = vec2(240.0_f32.min(layout.available_width()), 28.0);
+    let step = size.y + 4.0;
+    // Portrait screens have no room beside the HUD text, so the panel goes below it.
+    let (anchor, top) = if layout.is_portrait() {
+        (Anchor::TopLeft, -hud_height - 32.0)
+    } else {
+        (Anchor::TopRight, -32.0)
+    };
+    layout.place(anchor, vec2(0.0, top - index as f32 * step), size)
+}
+
+/// Buttons are stacked in the bottom right corner, or lined up along the bottom
+/// edge of a portrait screen.
+fn touch_button_aabb(layout: &Layout, index: usize) -> Aabb2<f32> {
+    let gap = 8.0;
+    if layout.is_portrait() {
+        let size = vec2(120.0, 48.0);
+        layout.place(
+            Anchor::BottomRight,
+            vec2(-(index as f32) * (size.x + gap), 0.0),
+            size,
+        )
+    } else {
+        let size = vec2(160.0, 48.0);
+        layout.place(
+            Anchor::BottomRight,
+            vec2(0.0, index as f32 * (size.y + gap)),
+            size,
+        )
+    }
+}
+
+/// Bottom of the backpack row above the bottom of the safe area, in reference pixels.
+fn backpack_bottom(layout: &Layout) -> f32 {
+    if layout.is_portrait() {
+        56.0
+    } else {
+        0.0
+    }
+}
+
+fn backpack_top(layout: &Layout) -> f32 {
+    backpack_bottom(layout) + 48.0
+}
+
+/// Slots shrink when there are too many of them to fit across the screen.
+fn backpack_slot_aabb(layout: &Layout, count: usize, slot: usize) -> Aabb2<f32> {
+    let gap = 8.0;
+    let fit = (layout.available_width() + gap) / count.max(1) as f32 - gap;
+    let size = fit.min(48.0);
+    let total_width = count as f32 * size + count.saturating_sub(1) as f32 * gap;
+    let left = -total_width / 2.0 + slot as f32 * (size + gap);
+    layout.place(
+        Anchor::BottomLeft,
+        vec2(
+            left + layout.available_width() / 2.0,
+            backpack_bottom(layout),
+        ),
+        vec2(size, size),
+    )
+}
+
+fn sound_caption_key(sound: &SoundKind) -> &'static str {
+    match sound {
+        SoundKind::TwoSteps => "caption.steps",
+        SoundKind::MetalHit => "caption.metal_hit",
+        SoundKind::PourWater => "caption.pour_water",
+        SoundKind::RobotMove => "caption.robot_move",
+        SoundKind::CoinPickup => "caption.coin_pickup",
+    }
+}
+
+fn enemy_key(kind: EnemyKind) -> &'static str {
+    match kind {
+        EnemyKind::Scout => "enemy.scout",
+        EnemyKind::Brute => "enemy.brute",
+        EnemyKind::Frost => "enemy.frost",
+    }
+}
+
+fn touch_button_key(button: UiButton) -> &'static str {
+    match button {
+        UiButton::UseItem => "touch.use_item",
+        UiButton::Cancel => "touch.cancel",
+        UiButton::Restart => "touch.restart",
+        UiButton::HighScores => "touch.high_scores",
+        UiButton::Mute => "touch.mute",
+        UiButton::Pause => "touch.pause",
+    }
+}
+
+fn ingredient_key(ingredient: &Ingredient) -> &'static str {
+    match ingredient {
+        Ingredient::Leaf => "ingredient.leaf",
+        Ingredient::Ice => "ingredient.ice",
+        Ingredient::Branch => "ingredient.branch",
+        Ingredient::Banana => "ingredient.banana",
+        Ingredient::Cherry => "ingredient.cherry",
+        Ingredient::Blueberry => "ingredient.blueberry",
+        Ingredient::Strawberry => "ingredient.strawberry",
+    }
+}
+
+fn potion_key(potion: &Potion) -> &'static str {
+    match potion {
+        Potion::Haste => "potion.haste",
+        Potion::Shield => "potion.shield",
+    }
+}
+
+fn gear_slot_key(slot: GearSlot) -> &'static str {
+    match slot {
+        GearSlot::Weapon => "gear_slot.weapon",
+        GearSlot::Armour => "gear_slot.armour",
+        GearSlot::Trinket => "gear_slot.trinket",
+    }
+}
+
+fn gear_key(gear: Gear) -> &'static str {
+    match gear {
+        Gear::Dagger => "gear.dagger",
+        Gear::Axe => "gear.axe",
+        Gear::LeatherArmour => "gear.leather_armour",
+        Gear::PlateArmour => "gear.plate_armour",
+        Gear::LuckyCharm => "gear.lucky_charm",
+    }
 }
@@ -0,0 +1,249 @@
+use crate::{model::*, settings::PaletteKind};
+
+use geng::prelude::*;
+
+/// Colours of the map and its highlights, chosen to stay distinguishable
+/// for the selected kind of colour vision.
+#[derive(Debug, Clone)]
+pub struct Palette {
+    pub empty_cell: Rgba<f32>,
+    pub ice: Rgba<f32>,
+    pub fire: Rgba<f32>,
+    pub swamp: Rgba<f32>,
+    /// Cells the map can be expanded to.
+    pub expansion: Rgba<f32>,
+    /// Cells covered by the brew being targeted.
+    pub targeting: Rgba<f32>,
+    pub scout: Rgba<f32>,
+    pub brute: Rgba<f32>,
+    pub frost: Rgba<f32>,
+    pub coins: Rgba<f32>,
+    pub weapon: Rgba<f32>,
+    pub armour: Rgba<f32>,
+    pub trinket: Rgba<f32>,
+    pub haste_potion: Rgba<f32>,
+    pub shield_potion: Rgba<f32>,
+    pub ice_ingredient: Rgba<f32>,
+    pub branch: Rgba<f32>,
+    pub banana: Rgba<f32>,
+    pub cherry: Rgba<f32>,
+    pub blueberry: Rgba<f32>,
+    pub strawberry: Rgba<f32>,
+    pub frozen: Rgba<f32>,
+    pub poisoned: Rgba<f32>,
+    pub burning: Rgba<f32>,
+    pub hasted: Rgba<f32>,
+    pub shielded: Rgba<f32>,
+    pub enemy_health: Rgba<f32>,
+    pub text: Rgba<f32>,
+    pub panel: Rgba<f32>,
+    pub panel_selected: Rgba<f32>,
+    /// Background of the equipped gear slots.
+    pub equipment_panel: Rgba<f32>,
+    pub night: Rgba<f32>,
+    /// Expansion frontier in the debug overlay.
+    pub debug_frontier: Rgba<f32>,
+    pub debug_text: Rgba<f32>,
+}
+
+impl Palette {
+    pub fn new(kind: PaletteKind, high_contrast: bool) -> Self {
+        let palette = match kind {
+            PaletteKind::Default => Self::default_colors(),
+            // Red and green look alike, so fire and swamp are told apart by blue.
+            PaletteKind::Deuteranopia | PaletteKind::Protanopia => Self {
+                ice: Rgba::new(0.35, 0.7, 0.9, 1.0),
+                fire: Rgba::new(0.9, 0.6, 0.0, 1.0),
+                swamp: Rgba::new(0.55, 0.3, 0.6, 1.0),
+                expansion: Rgba::new(0.0, 0.45, 0.7, 0.5),
+                targeting: Rgba::new(0.9, 0.6, 0.0, 0.4),
+                brute: Rgba::new(1.0, 0.75, 0.3, 1.0),
+                frost: Rgba::new(0.5, 0.7, 1.0, 1.0),
+                haste_potion: Rgba::new(1.0, 0.85, 0.0, 1.0),
+                shield_potion: Rgba::new(0.35, 0.55, 1.0, 1.0),
+                cherry: Rgba::new(0.85, 0.45, 0.0, 1.0),
+                blueberry: Rgba::new(0.2, 0.35, 0.8, 1.0),
+                strawberry: Rgba::new(0.8, 0.55, 0.95, 1.0),
+                poisoned: Rgba::new(0.7, 0.45, 0.8, 1.0),
+                burning: Rgba::new(0.95, 0.6, 0.0, 1.0),
+                hasted: Rgba::new(1.0, 1.0, 0.5, 1.0),
+                enemy_health: Rgba::new(1.0, 0.6, 0.0, 1.0),
+                ..Self::default_colors()
+            },
+            // Blue and yellow look alike, so ice and coins move towards cyan and red.
+            PaletteKind::Tritanopia => Self {
+                ice: Rgba::new(0.4, 0.85, 0.85, 1.0),
+                fire: Rgba::new(0.85, 0.2, 0.3, 1.0),
+                swamp: Rgba::new(0.3, 0.45, 0.3, 1.0),
+                expansion: Rgba::new(0.8, 0.3, 0.5, 0.5),
+                targeting: Rgba::new(0.85, 0.2, 0.3, 0.4),
+                frost: Rgba::new(0.5, 0.95, 0.95, 1.0),
+                coins: Rgba::new(1.0, 0.55, 0.6, 1.0),
+                trinket: Rgba::new(1.0, 0.55, 0.6, 1.0),
+                haste_potion: Rgba::new(1.0, 0.4, 0.55, 1.0),
+                shield_potion: Rgba::new(0.4, 0.85, 0.85, 1.0),
+                ice_ingredient: Rgba::new(0.5, 0.95, 0.95, 1.0),
+                banana: Rgba::new(1.0, 0.75, 0.8, 1.0),
+                cherry: Rgba::new(0.75, 0.05, 0.1, 1.0),
+                blueberry: Rgba::new(0.1, 0.45, 0.45, 1.0),
+                strawberry: Rgba::new(1.0, 0.45, 0.75, 1.0),
+                frozen: Rgba::new(0.5, 0.95, 0.95, 1.0),
+                hasted: Rgba::new(1.0, 0.4, 0.55, 1.0),
+                shielded: Rgba::new(0.6, 0.85, 0.85, 1.0),
+                ..Self::default_colors()
+            },
+        };
+        if high_contrast {
+            palette.high_contrast()
+        } else {
+            palette
+        }
+    }
+
+    fn default_colors() -> Self {
+        Self {
+            empty_cell: Rgba::new(0.2, 0.2, 0.2, 1.0),
+            ice: Rgba::new(0.5, 0.7, 0.9, 1.0),
+            fire: Rgba::new(0.6, 0.2, 0.1, 1.0),
+            swamp: Rgba::new(0.2, 0.35, 0.15, 1.0),
+            expansion: Rgba::new(0.3, 0.3, 0.1, 0.5),
+            targeting: Rgba::new(0.8, 0.3, 0.1, 0.4),
+            scout: Rgba::WHITE,
+            brute: Rgba::new(1.0, 0.6, 0.6, 1.0),
+            frost: Rgba::new(0.6, 0.8, 1.0, 1.0),
+            coins: Rgba::new(1.0, 0.85, 0.2, 1.0),
+            weapon: Rgba::new(0.7, 0.7, 1.0, 1.0),
+            armour: Rgba::new(0.6, 0.5, 0.4, 1.0),
+            trinket: Rgba::new(0.9, 0.8, 0.2, 1.0),
+            haste_potion: Rgba::new(1.0, 0.9, 0.2, 1.0),
+            shield_potion: Rgba::new(0.8, 0.8, 0.9, 1.0),
+            ice_ingredient: Rgba::new(0.6, 0.85, 1.0, 1.0),
+            branch: Rgba::new(0.5, 0.35, 0.2, 1.0),
+            banana: Rgba::new(1.0, 0.9, 0.3, 1.0),
+            cherry: Rgba::new(0.8, 0.1, 0.2, 1.0),
+            blueberry: Rgba::new(0.3, 0.3, 0.9, 1.0),
+            strawberry: Rgba::new(1.0, 0.3, 0.4, 1.0),
+            frozen: Rgba::new(0.4, 0.7, 1.0, 1.0),
+            poisoned: Rgba::new(0.3, 0.8, 0.2, 1.0),
+            burning: Rgba::new(1.0, 0.4, 0.1, 1.0),
+            hasted: Rgba::new(1.0, 0.9, 0.2, 1.0),
+            shielded: Rgba::new(0.8, 0.8, 0.9, 1.0),
+            enemy_health: Rgba::RED,
+            text: Rgba::WHITE,
+            panel: Rgba::new(0.2, 0.2, 0.2, 0.9),
+            panel_selected: Rgba::new(0.5, 0.5, 0.2, 0.9),
+            equipment_panel: Rgba::new(0.25, 0.2, 0.15, 0.9),
+            night: Rgba::new(0.0, 0.0, 0.1, 0.6),
+            debug_frontier: Rgba::new(0.2, 0.6, 1.0, 0.2),
+            debug_text: Rgba::new(1.0, 1.0, 0.0, 1.0),
+        }
+    }
+
+    /// Darkens the background and makes highlights and text fully opaque and brighter.
+    fn high_contrast(self) -> Self {
+        let brighten = |color: Rgba<f32>| {
+            let max = color.r.max(color.g).max(color.b).max(0.01);
+            Rgba::new(color.r / max, color.g / max, color.b / max, 1.0)
+        };
+        Self {
+            empty_cell: Rgba::new(0.05, 0.05, 0.05, 1.0),
+            ice: brighten(self.ice),
+            fire: brighten(self.fire),
+            swamp: brighten(self.swamp),
+            expansion: Rgba {
+                a: 0.8,
+                ..brighten(self.expansion)
+            },
+            targeting: Rgba {
+                a: 0.7,
+                ..brighten(self.targeting)
+            },
+            scout: brighten(self.scout),
+            brute: brighten(self.brute),
+            frost: brighten(self.frost),
+            coins: brighten(self.coins),
+            weapon: brighten(self.weapon),
+            armour: brighten(self.armour),
+            trinket: brighten(self.trinket),
+            haste_potion: brighten(self.haste_potion),
+            shield_potion: brighten(self.shield_potion),
+            ice_ingredient: brighten(self.ice_ingredient),
+            branch: brighten(self.branch),
+            banana: brighten(self.banana),
+            cherry: brighten(self.cherry),
+            blueberry: brighten(self.blueberry),
+            strawberry: brighten(self.strawberry),
+            frozen: brighten(self.frozen),
+            poisoned: brighten(self.poisoned),
+            burning: brighten(self.burning),
+            hasted: brighten(self.hasted),
+            shielded: brighten(self.shielded),
+            enemy_health: brighten(self.enemy_health),
+            text: Rgba::WHITE,
+            panel: Rgba::BLACK,
+            panel_selected: Rgba::new(0.6, 0.6, 0.0, 1.0),
+            equipment_panel: Rgba::new(0.15, 0.1, 0.0, 1.0),
+            night: Rgba::new(0.0, 0.0, 0.1, 0.4),
+            debug_frontier: Rgba {
+                a: 0.5,
+                ..brighten(self.debug_frontier)
+            },
+            debug_text: self.debug_text,
+        }
+    }
+
+    pub fn terrain(&self, terrain: Option<&Terrain>) -> Rgba<f32> {
+        match terrain {
+            None => self.empty_cell,
+            Some(Terrain::Ice) => self.ice,
+            Some(Terrain::Fire) => self.fire,
+            Some(Terrain::Swamp) => self.swamp,
+        }
+    }
+
+    pub fn gear(&self, slot: GearSlot) -> Rgba<f32> {
+        match slot {
+            GearSlot::Weapon => self.weapon,
+            GearSlot::Armour => self.armour,
+            GearSlot::Trinket => self.trinket,
+        }
+    }
+
+    pub fn potion(&self, potion: Potion) -> Rgba<f32> {
+        match potion {
+            Potion::Haste => self.haste_potion,
+            Potion::Shield => self.shield_potion,
+        }
+    }
+
+    pub fn ingredient(&self, ingredient: Ingredient) -> Rgba<f32> {
+        match ingredient {
+            // The leaf is drawn with its own sprite.
+            Ingredient::Leaf => Rgba::WHITE,
+            Ingredient::Ice => self.ice_ingredient,
+            Ingredient::Branch => self.branch,
+            Ingredient::Banana => self.banana,
+            Ingredient::Cherry => self.cherry,
+            Ingredient::Blueberry => self.blueberry,
+            Ingredient::Strawberry => self.strawberry,
+        }
+    }
+
+    pub fn status(&self, kind: StatusKind) -> Rgba<f32> {
+        match kind {
+            StatusKind::Frozen => self.frozen,
+            StatusKind::Poisoned => self.poisoned,
+            StatusKind::Burning => self.burning,
+            StatusKind::Hasted => self.hasted,
+            StatusKind::Shielded => self.shielded,
+        }
+    }
+
+    pub fn enemy_tint(&self, kind: EnemyKind) -> Rgba<f32> {
+        match kind {
+            EnemyKind::Scout => self.scout,
+            EnemyKind::Brute => self.brute,
+            EnemyKind::Frost => self.frost,
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{audio::AudioSettings, locale::Language};
+
+use geng::prelude::*;
+
+const PREFERENCES_KEY: &str = "settings";
+
+/// Window sizes offered on the settings screen.
+pub const WINDOW_SIZES: [(usize, usize); 4] = [(1024, 768), (1280, 720), (1600, 900), (1920, 1080)];
+
+/// Player settings, stored in the user's config directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub audio: AudioSettings,
+    pub keys: KeyBindings,
+    pub language: Language,
+    pub fullscreen: bool,
+    pub window_size: vec2<usize>,
+    /// Multiplier for the speed of animations and night turns.
+    pub animation_speed: f32,
+    pub palette: PaletteKind,
+    pub high_contrast: bool,
+    /// Multiplier for the size of text drawn over the map and in the HUD.
+    pub text_scale: f32,
+    /// Turns off tweens, rising popups and the night fade.
+    pub reduced_motion: bool,
+    /// Shows a caption for every sound effect.
+    pub captions: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            audio: AudioSettings::default(),
+            keys: KeyBindings::default(),
+            language: Language::default(),
+            fullscreen: false,
+            window_size: vec2(1280, 720),
+            animation_speed: 1.0,
+            palette: PaletteKind::default(),
+            high_contrast: false,
+            text_scale: 1.0,
+            reduced_motion: false,
+            captions: false,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        preferences::load(PREFERENCES_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        preferences::save(PREFERENCES_KEY, self);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaletteKind {
+    #[default]
+    Default,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+}
+
+impl PaletteKind {
+    pub const ALL: [PaletteKind; 4] = [
+        PaletteKind::Default,
+        PaletteKind::Deuteranopia,
+        PaletteKind::Protanopia,
+        PaletteKind::Tritanopia,
+    ];
+}
+
+/// Keys the game handles itself, which cannot be bound to a [Control].
+pub const RESERVED_KEYS: [geng::Key; 14] = [
+    geng::Key::Digit1,
+    geng::Key::Digit2,
+    geng::Key::Digit3,
+    geng::Key::Digit4,
+    geng::Key::Digit5,
+    geng::Key::Digit6,
+    geng::Key::Digit7,
+    geng::Key::Digit8,
+    geng::Key::Digit9,
+    geng::Key::KeyZ,
+    geng::Key::KeyX,
+    geng::Key::KeyC,
+    geng::Key::F3,
+    geng::Key::Backquote,
+];
+
+/// Game controls that can be rebound on the settings screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Pause,
+    Mute,
+    HighScores,
+    Restart,
+}
+
+impl Control {
+    pub const ALL: [Control; 8] = [
+        Control::MoveUp,
+        Control::MoveDown,
+        Control::MoveLeft,
+        Control::MoveRight,
+        Control::Pause,
+        Control::Mute,
+        Control::HighScores,
+        Control::Restart,
+    ];
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub move_up: Vec<geng::Key>,
+    pub move_down: Vec<geng::Key>,
+    pub move_left: Vec<geng::Key>,
+    pub move_right: Vec<geng::Key>,
+    pub pause: Vec<geng::Key>,
+    pub mute: Vec<geng::Key>,
+    pub high_scores: Vec<geng::Key>,
+    pub restart: Vec<geng::Key>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            move_up: vec![geng::Key::ArrowUp, geng::Key::KeyW],
+            move_down: vec![geng::Key::ArrowDown, geng::Key::KeyS],
+            move_left: vec![geng::Key::ArrowLeft, geng::Key::KeyA],
+            move_right: vec![geng::Key::ArrowRight, geng::Key::KeyD],
+            pause: vec![geng::Key::Escape, geng::Key::KeyP],
+            mute: vec![geng::Key::KeyM],
+            high_scores: vec![geng::Key::KeyH],
+            restart: vec![geng::Key::KeyR],
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn get(&self, control: Control) -> &[geng::Key] {
+        match control {
+            Control::MoveUp => &self.move_up,
+            Control::MoveDown => &self.move_down,
+            Control::MoveLeft => &self.move_left,
+            Control::MoveRight => &self.move_right,
+            Control::Pause => &self.pause,
+            Control::Mute => &self.mute,
+            Control::HighScores => &self.high_scores,
+            Control::Restart => &self.restart,
+        }
+    }
+
+    fn get_mut(&mut self, control: Control) -> &mut Vec<geng::Key> {
+        match control {
+            Control::MoveUp => &mut self.move_up,
+            Control::MoveDown => &mut self.move_down,
+            Control::MoveLeft => &mut self.move_left,
+            Control::MoveRight => &mut self.move_right,
+            Control::Pause => &mut self.pause,
+            Control::Mute => &mut self.mute,
+            Control::HighScores => &mut self.high_scores,
+            Control::Restart => &mut self.restart,
+        }
+    }
+
+    pub fn control(&self, key: geng::Key) -> Option<Control> {
+        Control::ALL
+            .into_iter()
+            .find(|&control| self.get(control).contains(&key))
+    }
+
+    /// Makes `key` the only key for `control`, taking it away from any other control.
+    /// Returns `false` and leaves the bindings alone if `key` is one of the [RESERVED_KEYS].
+    pub fn rebind(&mut self, control: Control, key: geng::Key) -> bool {
+        if RESERVED_KEYS.contains(&key) {
+            return false;
+        }
+        for other in Control::ALL {
+            self.get_mut(other).retain(|&bound| bound != key);
+        }
+        *self.get_mut(control) = vec![key];
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebinding_takes_the_key_from_other_controls() {
+        let mut keys = KeyBindings::default();
+        assert!(keys.rebind(Control::Mute, geng::Key::KeyW));
+        assert_eq!(keys.get(Control::Mute), [geng::Key::KeyW]);
+        assert_eq!(keys.get(Control::MoveUp), [geng::Key::ArrowUp]);
+        assert_eq!(keys.control(geng::Key::KeyW), Some(Control::Mute));
+    }
+
+    #[test]
+    fn reserved_keys_cannot_be_bound() {
+        for key in RESERVED_KEYS {
+            let mut keys = KeyBindings::default();
+            assert!(!keys.rebind(Control::Pause, key), "{key:?}");
+            assert_eq!(keys.get(Control::Pause), KeyBindings::default().pause);
+            assert_eq!(keys.control(key), None);
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use crate::model::*;
+
+use geng::prelude::*;
+
+/// An automated player.
+pub trait Agent {
+    /// Picks the next action. Only called while the model is waiting for player input.
+    fn act(&mut self, model: &Model) -> Action;
+}
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+pub enum AgentKind {
+    /// Moves and uses items at random.
+    Random,
+    /// Always walks towards the sword.
+    #[default]
+    Greedy,
+    /// Heals and shields early and ends the day away from enemies.
+    Cautious,
+}
+
+impl AgentKind {
+    pub fn create(self, seed: u64) -> Box<dyn Agent> {
+        let rng = StdRng::seed_from_u64(seed);
+        match self {
+            AgentKind::Random => Box::new(RandomAgent { rng }),
+            AgentKind::Greedy => Box::new(GreedyAgent { rng }),
+            AgentKind::Cautious => Box::new(CautiousAgent { rng }),
+        }
+    }
+}
+
+pub struct RandomAgent {
+    rng: StdRng,
+}
+
+impl Agent for RandomAgent {
+    fn act(&mut self, model: &Model) -> Action {
+        if let Some(action) = common_action(model, &mut self.rng) {
+            return action;
+        }
+        let ingredients = model.player.backpack.ingredients.len();
+        if ingredients > 0 && self.rng.gen_bool(0.2) {
+            return Action::UseItem(self.rng.gen_range(0..ingredients));
+        }
+        random_move(&mut self.rng)
+    }
+}
+
+pub struct GreedyAgent {
+    rng: StdRng,
+}
+
+impl Agent for GreedyAgent {
+    fn act(&mut self, model: &Model) -> Action {
+        if let Some(action) = common_action(model, &mut self.rng) {
+            return action;
+        }
+        if let Some(action) = better_gear(model) {
+            return action;
+        }
+        match sword_step(model) {
+            Some(step) => Action::MoveTo(step),
+            None => random_move(&mut self.rng),
+        }
+    }
+}
+
+pub struct CautiousAgent {
+    rng: StdRng,
+}
+
+impl Agent for CautiousAgent {
+    fn act(&mut self, model: &Model) -> Action {
+        if let Some(action) = common_action(model, &mut self.rng) {
+            return action;
+        }
+        if let Some(action) = better_gear(model) {
+            return action;
+        }
+
+        let player = &model.player;
+        if player.health * 5 < player.max_health * 3 {
+            if let Some(slot) = find_ingredient(model, Ingredient::Leaf) {
+                return Action::UseItem(slot);
+            }
+        }
+
+        let threatened = model
+            .level_map
+            .enemies()
+            .iter()
+            .any(|enemy| manhattan_dist(enemy.pos, player.pos) <= 2);
+        if threatened && !player.statuses.has(StatusKind::Shielded) {
+            if let Some(slot) = find_ingredient(model, Ingredient::Blueberry) {
+                return Action::UseItem(slot);
+            }
+        }
+        if let Some(slot) = model
+            .player
+            .backpack
+            .ingredients
+            .iter()
+            .position(|ingredient| ingredient.brew().needs_target())
+        {
+            if !model.level_map.enemies().is_empty() {
+                return Action::UseItem(slot);
+            }
+        }
+
+        if player.turns_remaining <= 1 {
+            if let Some(step) = safest_step(model) {
+                return Action::MoveTo(step);
+            }
+        }
+        match sword_step(model) {
+            Some(step) => Action::MoveTo(step),
+            None => random_move(&mut self.rng),
+        }
+    }
+}
+
+/// Handles the states every agent treats the same way: picking an expansion cell and
+/// choosing a target for an item that needs one.
+fn common_action(model: &Model, rng: &mut impl Rng) -> Option<Action> {
+    if let State::ExpandMap = model.state {
+        let cell = model.level_map.adjacent_cells_iter().choose(rng)?;
+        return Some(Action::MoveTo(cell));
+    }
+    if model.targeting.is_some() {
+        let target = model
+            .level_map
+            .enemies()
+            .iter()
+            .min_by_key(|enemy| manhattan_dist(enemy.pos, model.player.pos));
+        return Some(match target {
+            Some(enemy) => Action::MoveTo(enemy.pos),
+            None => Action::Cancel,
+        });
+    }
+    None
+}
+
+fn random_move(rng: &mut impl Rng) -> Action {
+    match rng.gen_range(0..4) {
+        0 => Action::MoveUp,
+        1 => Action::MoveDown,
+        2 => Action::MoveLeft,
+        _ => Action::MoveRight,
+    }
+}
+
+fn find_ingredient(model: &Model, wanted: Ingredient) -> Option<usize> {
+    model
+        .player
+        .backpack
+        .ingredients
+        .iter()
+        .position(|ingredient| *ingredient == wanted)
+}
+
+/// Equips carried gear that is strictly better than what is in its slot.
+fn better_gear(model: &Model) -> Option<Action> {
+    let power = |gear: &Gear| gear.attack() + gear.defense();
+    model
+        .player
+        .backpack
+        .gear
+        .iter()
+        .position(|gear| match model.player.equipment.get(gear.slot()) {
+            Some(equipped) => power(gear) > power(&equipped),
+            None => true,
+        })
+        .map(Action::Equip)
+}
+
+fn walkable(model: &Model, pos: vec2<i64>) -> bool {
+    model.level_map.inside(pos) && !model.level_map.has_enemy_at(pos)
+}
+
+fn neighbours(pos: vec2<i64>) -> impl Iterator<Item = vec2<i64>> {
+    [vec2(-1, 0), vec2(1, 0), vec2(0, -1), vec2(0, 1)]
+        .into_iter()
+        .map(move |delta| pos + delta)
+}
+
+/// First step of a shortest path from the player to the sword.
+fn sword_step(model: &Model) -> Option<vec2<i64>> {
+    let sword = model
+        .level_map
+        .items()
+        .iter()
+        .find(|item| matches!(item.kind, ItemKind::Sword { .. }))?;
+    if model.level_map.enemies().is_empty() {
+        return None;
+    }
+
+    let start = model.player.pos;
+    let mut first_steps = HashMap::new();
+    let mut queue = VecDeque::new();
+    for step in neighbours(start).filter(|&pos| walkable(model, pos)) {
+        first_steps.insert(step, step);
+        queue.push_back(step);
+    }
+    while let Some(pos) = queue.pop_front() {
+        let first = first_steps[&pos];
+        if pos == sword.pos {
+            return Some(first);
+        }
+        for next in neighbours(pos) {
+            if next != start && walkable(model, next) && !first_steps.contains_key(&next) {
+                first_steps.insert(next, first);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Neighbouring cell furthest away from the closest enemy.
+fn safest_step(model: &Model) -> Option<vec2<i64>> {
+    neighbours(model.player.pos)
+        .filter(|&pos| walkable(model, pos))
+        .max_by_key(|&pos| {
+            model
+                .level_map
+                .enemies()
+                .iter()
+                .map(|enemy| manhattan_dist(enemy.pos, pos))
+                .min()
+                .unwrap_or(i64::MAX)
+        })
+}
@@ -0,0 +1,102 @@
+mod agent;
+
+pub use self::agent::*;
+
+use crate::model::*;
+
+use geng::prelude::*;
+
+pub struct SimConfig {
+    pub games: usize,
+    pub first_seed: u64,
+    pub agent: AgentKind,
+    /// A run counts as won once the player has survived this many nights.
+    pub target_days: u64,
+    /// Actions after which a run that is neither won nor lost is given up.
+    pub max_actions: usize,
+}
+
+pub struct GameResult {
+    pub won: bool,
+    pub days_survived: u64,
+    pub turns: u64,
+}
+
+/// Plays a single game without a window, skipping over animations.
+pub fn play(seed: u64, config: &SimConfig) -> GameResult {
+    let mut model = Model::new(seed);
+    model.validate_inputs = true;
+    let mut agent = config.agent.create(seed);
+    let mut actions = 0;
+    loop {
+        settle(&mut model);
+        if model.score().days_survived >= config.target_days {
+            break;
+        }
+        match model.state {
+            State::Day | State::ExpandMap if actions < config.max_actions => {
+                let action = agent.act(&model);
+                model.player_input(action);
+                actions += 1;
+            }
+            _ => break,
+        }
+    }
+
+    let score = model.score();
+    GameResult {
+        won: score.days_survived >= config.target_days,
+        days_survived: score.days_survived,
+        turns: model.stats.turns_taken,
+    }
+}
+
+/// Fast-forwards the timeline until nothing is scheduled, e.g. through a whole night.
+fn settle(model: &mut Model) {
+    model.effects.clear();
+    while let Some(at) = model.timeline.next_event_at() {
+        model.update((at - model.now()).max(r32(0.0)));
+        model.effects.clear();
+    }
+}
+
+pub struct SimReport {
+    pub results: Vec<GameResult>,
+}
+
+impl SimReport {
+    pub fn win_rate(&self) -> f64 {
+        self.average(|result| if result.won { 1.0 } else { 0.0 })
+    }
+
+    pub fn average_days(&self) -> f64 {
+        self.average(|result| result.days_survived as f64)
+    }
+
+    pub fn average_turns(&self) -> f64 {
+        self.average(|result| result.turns as f64)
+    }
+
+    fn average(&self, f: impl Fn(&GameResult) -> f64) -> f64 {
+        if self.results.is_empty() {
+            return 0.0;
+        }
+        self.results.iter().map(f).sum::<f64>() / self.results.len() as f64
+    }
+}
+
+pub fn run(config: &SimConfig) -> SimReport {
+    let results = (0..config.games as u64)
+        .map(|i| play(config.first_seed + i, config))
+        .collect();
+    SimReport { results }
+}
+
+impl std::fmt::Display for SimReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "games played:          {}", self.results.len())?;
+        writeln!(f, "win rate:              {:.1}%", self.win_rate() * 100.0)?;
+        writeln!(f, "average days survived: {:.2}", self.average_days())?;
+        write!(f, "average turns:         {:.1}", self.average_turns())
+    }
+}
@@ -0,0 +1,120 @@
+use geng::prelude::*;
+
+/// Distance in pixels a finger has to travel for a touch to count as a swipe.
+const SWIPE_DISTANCE: f64 = 40.0;
+/// Seconds a finger has to rest in place for a touch to count as a long press.
+const LONG_PRESS_TIME: f64 = 0.5;
+
+pub enum Gesture {
+    Tap(vec2<f64>),
+    Swipe(SwipeDirection),
+    LongPress(vec2<f64>),
+    /// Two fingers moved apart or together; the value is the ratio of the previous
+    /// distance between them to the current one.
+    Pinch(f64),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SwipeDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+struct TrackedTouch {
+    id: u64,
+    start: vec2<f64>,
+    position: vec2<f64>,
+    start_time: f64,
+    /// Set once the touch turned into a long press or a pinch, so releasing it does nothing.
+    consumed: bool,
+}
+
+/// Turns raw touch events into gestures.
+#[derive(Default)]
+pub struct TouchInput {
+    touches: Vec<TrackedTouch>,
+    time: f64,
+}
+
+impl TouchInput {
+    pub fn touch_start(&mut self, touch: geng::Touch) {
+        self.touches.push(TrackedTouch {
+            id: touch.id,
+            start: touch.position,
+            position: touch.position,
+            start_time: self.time,
+            consumed: false,
+        });
+    }
+
+    pub fn touch_move(&mut self, touch: geng::Touch) -> Option<Gesture> {
+        let old_distance = self.pinch_distance();
+        let tracked = self
+            .touches
+            .iter_mut()
+            .find(|tracked| tracked.id == touch.id)?;
+        tracked.position = touch.position;
+        let new_distance = self.pinch_distance();
+
+        let (Some(old_distance), Some(new_distance)) = (old_distance, new_distance) else {
+            return None;
+        };
+        for tracked in &mut self.touches {
+            tracked.consumed = true;
+        }
+        (new_distance > 0.0).then(|| Gesture::Pinch(old_distance / new_distance))
+    }
+
+    pub fn touch_end(&mut self, touch: geng::Touch) -> Option<Gesture> {
+        let index = self
+            .touches
+            .iter()
+            .position(|tracked| tracked.id == touch.id)?;
+        let tracked = self.touches.remove(index);
+        if tracked.consumed {
+            return None;
+        }
+
+        let delta = touch.position - tracked.start;
+        if delta.len() < SWIPE_DISTANCE {
+            return Some(Gesture::Tap(touch.position));
+        }
+        // Screen coordinates grow upwards, same as the world.
+        let direction = if delta.x.abs() > delta.y.abs() {
+            if delta.x > 0.0 {
+                SwipeDirection::Right
+            } else {
+                SwipeDirection::Left
+            }
+        } else if delta.y > 0.0 {
+            SwipeDirection::Up
+        } else {
+            SwipeDirection::Down
+        };
+        Some(Gesture::Swipe(direction))
+    }
+
+    pub fn update(&mut self, delta_time: f64) -> Option<Gesture> {
+        self.time += delta_time;
+        let [tracked] = self.touches.as_mut_slice() else {
+            return None;
+        };
+        if tracked.consumed
+            || self.time - tracked.start_time < LONG_PRESS_TIME
+            || (tracked.position - tracked.start).len() >= SWIPE_DISTANCE
+        {
+            return None;
+        }
+        tracked.consumed = true;
+        Some(Gesture::LongPress(tracked.position))
+    }
+
+    fn pinch_distance(&self) -> Option<f64> {
+        match self.touches.as_slice() {
+            [first, second] => Some((first.position - second.position).len()),
+            _ => None,
+        }
+    }
+}